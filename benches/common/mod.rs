@@ -0,0 +1,117 @@
+//! The workloads shared by the benchmarks, and the baseline that `BiMap` is compared against.
+//! This is also compiled into the `bench_harness` test, which checks that the baseline behaves in
+//! the same way as `BiMap`.
+use isomorphism::BiMap;
+
+use std::collections::HashMap;
+
+/// The operations that the benchmarks measure, for both `BiMap` and the baseline.
+pub trait Pairs {
+    fn new() -> Self;
+    fn insert(&mut self, left: u64, right: u64);
+    fn get_left(&self, left: &u64) -> Option<&u64>;
+    fn get_right(&self, right: &u64) -> Option<&u64>;
+    fn remove_left(&mut self, left: &u64) -> Option<u64>;
+    fn len(&self) -> usize;
+}
+
+impl Pairs for BiMap<u64, u64> {
+    fn new() -> Self {
+        BiMap::new()
+    }
+
+    fn insert(&mut self, left: u64, right: u64) {
+        BiMap::insert(self, left, right);
+    }
+
+    fn get_left(&self, left: &u64) -> Option<&u64> {
+        BiMap::get_left(self, left)
+    }
+
+    fn get_right(&self, right: &u64) -> Option<&u64> {
+        BiMap::get_right(self, right)
+    }
+
+    fn remove_left(&mut self, left: &u64) -> Option<u64> {
+        BiMap::remove_left(self, left)
+    }
+
+    fn len(&self) -> usize {
+        BiMap::len(self)
+    }
+}
+
+/// The naive way of mapping in both directions: a `HashMap` for each direction, which are kept in
+/// sync by hand, with the same overwriting behaviour as `BiMap::insert`.
+#[derive(Debug, Default)]
+pub struct TwoMaps {
+    left_to_right: HashMap<u64, u64>,
+    right_to_left: HashMap<u64, u64>,
+}
+
+impl Pairs for TwoMaps {
+    fn new() -> Self {
+        Default::default()
+    }
+
+    fn insert(&mut self, left: u64, right: u64) {
+        if let Some(old_right) = self.left_to_right.remove(&left) {
+            self.right_to_left.remove(&old_right);
+        }
+        if let Some(old_left) = self.right_to_left.remove(&right) {
+            self.left_to_right.remove(&old_left);
+        }
+        self.left_to_right.insert(left, right);
+        self.right_to_left.insert(right, left);
+    }
+
+    fn get_left(&self, left: &u64) -> Option<&u64> {
+        self.left_to_right.get(left)
+    }
+
+    fn get_right(&self, right: &u64) -> Option<&u64> {
+        self.right_to_left.get(right)
+    }
+
+    fn remove_left(&mut self, left: &u64) -> Option<u64> {
+        let right = self.left_to_right.remove(left)?;
+        self.right_to_left.remove(&right);
+        Some(right)
+    }
+
+    fn len(&self) -> usize {
+        self.left_to_right.len()
+    }
+}
+
+/// The pairs used by every workload. Keys are spread out rather than sequential, and the right
+/// keys are a different permutation to the left keys.
+pub fn pairs(count: u64) -> Vec<(u64, u64)> {
+    (0..count)
+        .map(|i| (i.wrapping_mul(0x9e37_79b9_7f4a_7c15), i.wrapping_mul(0xc2b2_ae3d_27d4_eb4f)))
+        .collect()
+}
+
+/// Builds a map from pairs, one insert at a time.
+pub fn insert_all<M: Pairs>(pairs: &[(u64, u64)]) -> M {
+    let mut map = M::new();
+    for &(left, right) in pairs {
+        map.insert(left, right);
+    }
+    map
+}
+
+/// Looks up every left key, returning how many were found.
+pub fn get_left_all<M: Pairs>(map: &M, pairs: &[(u64, u64)]) -> usize {
+    pairs.iter().filter(|&&(left, right)| map.get_left(&left) == Some(&right)).count()
+}
+
+/// Looks up every right key, returning how many were found.
+pub fn get_right_all<M: Pairs>(map: &M, pairs: &[(u64, u64)]) -> usize {
+    pairs.iter().filter(|&&(left, right)| map.get_right(&right) == Some(&left)).count()
+}
+
+/// Removes every pair by its left key, returning how many were removed.
+pub fn remove_left_all<M: Pairs>(map: &mut M, pairs: &[(u64, u64)]) -> usize {
+    pairs.iter().filter(|&&(left, right)| map.remove_left(&left) == Some(right)).count()
+}
@@ -0,0 +1,42 @@
+//! Compares the throughput of `BiMap` against a pair of `HashMap`s kept in sync.
+#[macro_use]
+extern crate criterion;
+extern crate isomorphism;
+
+mod common;
+
+use common::{Pairs, TwoMaps};
+use criterion::{BatchSize, Criterion};
+use isomorphism::BiMap;
+
+const PAIRS: u64 = 10_000;
+
+fn bench<M: Pairs>(c: &mut Criterion, name: &str) {
+    let pairs = common::pairs(PAIRS);
+    let map: M = common::insert_all(&pairs);
+    assert_eq!(PAIRS as usize, map.len());
+
+    let mut group = c.benchmark_group(name);
+    group.bench_function("insert", |b| b.iter(|| common::insert_all::<M>(&pairs)));
+    group.bench_function("get_left", |b| b.iter(|| common::get_left_all(&map, &pairs)));
+    group.bench_function("get_right", |b| b.iter(|| common::get_right_all(&map, &pairs)));
+    group.bench_function("remove_left", |b| {
+        b.iter_batched(
+            || common::insert_all::<M>(&pairs),
+            |mut map| common::remove_left_all(&mut map, &pairs),
+            BatchSize::LargeInput,
+        )
+    });
+    group.finish();
+}
+
+fn bimap(c: &mut Criterion) {
+    bench::<BiMap<u64, u64>>(c, "BiMap");
+}
+
+fn two_maps(c: &mut Criterion) {
+    bench::<TwoMaps>(c, "TwoMaps");
+}
+
+criterion_group!(benches, bimap, two_maps);
+criterion_main!(benches);
@@ -17,12 +17,24 @@ pub trait BitField: BitAnd<Output = Self> + BitOr<Output = Self> + Copy + Sized
     /// Should return a bitfield that is all ones, except for a single zero at a given index.
     fn zero_at(index: usize) -> Self;
 
+    /// Returns a bitfield that is all zeroes. The default implementation clears the only one in
+    /// `one_at(0)`.
+    fn zero() -> Self {
+        Self::one_at(0) & Self::zero_at(0)
+    }
+
     /// Return an iterator that iterates through the bitfield, returning the indexes within the
     /// bitfield that have 1s in them, in order from least significant to most significant.
     fn iter(&self) -> Self::Iter;
 
     /// Is the bitfield currently full?
     fn full(&self) -> bool;
+
+    /// Is the bitfield currently empty? The default implementation checks whether `iter` returns
+    /// anything.
+    fn empty(&self) -> bool {
+        self.iter().next().is_none()
+    }
 }
 
 mod private {
@@ -86,6 +98,10 @@ mod private {
             !Self::one_at(index)
         }
 
+        fn zero() -> Self {
+            Self::from(0)
+        }
+
         fn iter(&self) -> Self::Iter {
             BitFieldIterator(*self, 0)
         }
@@ -93,6 +109,10 @@ mod private {
         fn full(&self) -> bool {
             *self == Self::one_at(0) | Self::zero_at(0)
         }
+
+        fn empty(&self) -> bool {
+            *self == Self::from(0)
+        }
     }
 }
 
@@ -150,11 +170,23 @@ mod test {
         }
     }
 
+    #[test]
+    fn zero_is_empty() {
+        assert!(u8::zero().empty());
+        assert_eq!(0, u64::zero().iter().count());
+    }
+
+    quickcheck! {
+        fn empty_iff_no_bits(input: u16) -> bool {
+            input.empty() == (input.iter().count() == 0)
+        }
+    }
+
     quickcheck! {
         fn iterator_results_equal_number(input: u32) -> bool {
             input == input.iter()
                 .map(|x| 1 << x)
-                .fold(0, |x, y| x + y)
+                .sum::<u32>()
         }
     }
 }
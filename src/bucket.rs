@@ -1,4 +1,12 @@
 use bitfield::BitField;
+use error::TryReserveError;
+
+use std::hint;
+use std::mem;
+
+/// The cross-index of a left key that hasn't been paired with a right key yet, as inserted by
+/// `insert_left_only`.
+pub const INCOMPLETE: usize = usize::MAX;
 
 /// A single bucket within a hopscotch hashed hashmap.
 #[derive(Clone, Debug)]
@@ -10,24 +18,72 @@ pub struct Bucket<K, V, B> {
     /// bucket, a zero in this bitfield means that the bucket is either empty, or contains a value
     /// which should not be in this bucket.
     pub neighbourhood: B,
+    /// Incremented whenever a key is stored in this bucket, so that a handle to a pair that has
+    /// since been removed can't refer to a pair that later takes its place.
+    pub generation: usize,
 }
 
 impl<K, V, B: BitField + Copy> Bucket<K, V, B> {
+    /// Create a new bucket, with no data in it and an empty neighbourhood.
+    pub fn empty() -> Self {
+        Bucket {
+            data: None,
+            neighbourhood: B::zero(),
+            generation: 0,
+        }
+    }
+
     /// Create a new heap allocated array, with a given size, of empty buckets.
     pub fn empty_vec(size: usize) -> Box<[Self]> {
         let mut output = Vec::with_capacity(size);
 
         for _ in 0..size {
-            let element: Self = Bucket {
-                data: None,
-                neighbourhood: B::one_at(0) & B::zero_at(0),
-            };
-
-            output.push(element);
+            output.push(Self::empty());
         }
 
         output.into()
     }
+
+    /// Create a new heap allocated array, with a given size, of empty buckets. Returns an error
+    /// instead of panicking if the array could not be allocated.
+    pub fn try_empty_vec(size: usize) -> Result<Box<[Self]>, TryReserveError> {
+        match size.checked_mul(mem::size_of::<Self>()) {
+            Some(bytes) if bytes <= isize::MAX as usize => (),
+            _ => return Err(TryReserveError::CapacityOverflow),
+        }
+
+        let mut output = Vec::new();
+        output
+            .try_reserve_exact(size)
+            .map_err(|_| TryReserveError::AllocError)?;
+
+        for _ in 0..size {
+            output.push(Self::empty());
+        }
+
+        Ok(output.into())
+    }
+
+    /// Writes to every bucket of an array, so that all of the memory behind it has been faulted
+    /// in by the time this returns, however the allocator provided it.
+    pub fn prefault(buckets: &mut [Self]) {
+        for bucket in buckets.iter_mut() {
+            // the optimiser can't see the value being written, so it can't skip the writes, or
+            // turn the whole array into zeroed pages that are only faulted in when first used
+            bucket.generation = hint::black_box(0);
+        }
+    }
+}
+
+impl<K, B> Bucket<K, usize, B> {
+    /// Returns the key in this bucket and the index of the key it is paired with, if the bucket
+    /// holds a key that is paired at all. Empty buckets and incomplete left keys give None.
+    pub fn pair(&self) -> Option<(&K, usize)> {
+        match self.data {
+            Some((ref key, pair_index, _)) if pair_index != INCOMPLETE => Some((key, pair_index)),
+            _ => None,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -38,7 +94,7 @@ mod tests {
     #[test]
     fn test_empty_vec() {
         let vec: Box<[Bucket<(), (), DefaultBitField>]> = Bucket::empty_vec(0);
-        assert!(vec.len() == 0)
+        assert!(vec.is_empty())
     }
 
     #[test]
@@ -48,7 +104,7 @@ mod tests {
         assert!(vec.len() == length);
         assert!(
             vec.iter()
-                .all(|element| element.data == None && element.neighbourhood == 0)
+                .all(|element| element.data.is_none() && element.neighbourhood == 0)
         );
     }
 }
@@ -1,27 +1,53 @@
-use {BiMap, DEFAULT_HASH_MAP_SIZE, MAX_LOAD_FACTOR};
+use {default_hasher, BiMap, DEFAULT_HASH_MAP_SIZE, MAX_LOAD_FACTOR};
 use bitfield::{BitField, DefaultBitField};
 use bucket::Bucket;
+use order::Order;
 
 use std::cmp;
 use std::collections::hash_map::RandomState;
 use std::hash::BuildHasher;
 use std::marker::PhantomData;
 
+/// What a bimap does when a pair can't be placed within its neighbourhood, although the bimap
+/// isn't full. See the `resize_policy` method of `BiMapBuilder`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ResizePolicy {
+    /// Double the number of buckets straight away, as when the bimap is full.
+    Grow,
+    /// First try moving every pair again within the buckets the bimap already has, with new
+    /// hashers if the bimap reseeds (see `reseed_on_collisions`). This breaks up clusters of keys
+    /// that only collide under the old seeds without allocating anything, and doesn't count as a
+    /// resize. It is tried once for each time the bimap would have grown: if a pair still can't
+    /// be placed, the number of buckets is doubled.
+    RedistributeFirst,
+}
+
 /// A builder for the bimap. Allows for the parameters used to tune the BiMap to be configured.
 #[derive(Debug)]
 pub struct BiMapBuilder<LH, RH, B> {
     capacity: usize,
+    ordered: bool,
+    resize_policy: ResizePolicy,
+    reseed: Option<fn() -> (LH, RH)>,
     left_hasher: LH,
     right_hasher: RH,
     bit_field: PhantomData<B>,
 }
 
+/// Creates a new pair of hashers, which for hashers such as `RandomState` have new random seeds.
+fn fresh_hashers<LH: Default, RH: Default>() -> (LH, RH) {
+    (LH::default(), RH::default())
+}
+
 impl Default for BiMapBuilder<RandomState, RandomState, DefaultBitField> {
     fn default() -> Self {
         BiMapBuilder {
             capacity: DEFAULT_HASH_MAP_SIZE,
-            left_hasher: Default::default(),
-            right_hasher: Default::default(),
+            ordered: false,
+            resize_policy: ResizePolicy::Grow,
+            reseed: Some(fresh_hashers),
+            left_hasher: default_hasher(),
+            right_hasher: default_hasher(),
             bit_field: Default::default(),
         }
     }
@@ -43,7 +69,8 @@ impl<LH: BuildHasher, RH: BuildHasher, B: BitField> BiMapBuilder<LH, RH, B> {
     /// Sets the initial capacity of the bimap. It is not guaranteed that at least `capacity`
     /// elements can be inserted before the map needs to be resized, but it is likely. The only
     /// reason the map would need to be resized before that number of elements was inserted is due
-    /// to a large number of hash collisions.
+    /// to a large number of hash collisions. Small capacities (including 0) are rounded up to a
+    /// reasonable minimum.
     ///
     /// ```
     /// # use isomorphism::{BiMap, BiMapBuilder};
@@ -53,6 +80,79 @@ impl<LH: BuildHasher, RH: BuildHasher, B: BitField> BiMapBuilder<LH, RH, B> {
         BiMapBuilder { capacity, ..self }
     }
 
+    /// Makes the bimap keep track of the order in which pairs were inserted, so that they can be
+    /// visited in that order by `iter_ordered`. This costs some extra memory for every bucket and
+    /// every pair, and makes inserts and removals slightly slower.
+    ///
+    /// ```
+    /// # use isomorphism::{BiMap, BiMapBuilder};
+    /// let mut map: BiMap<&str, u64> = BiMapBuilder::new().ordered().finish();
+    /// map.insert("World", 7);
+    /// map.insert("Hello", 5);
+    ///
+    /// let pairs: Vec<_> = map.iter_ordered().unwrap().collect();
+    /// assert_eq!(vec![(&"World", &7), (&"Hello", &5)], pairs);
+    /// ```
+    pub fn ordered(self) -> Self {
+        BiMapBuilder {
+            ordered: true,
+            ..self
+        }
+    }
+
+    /// Sets what the bimap does when a pair can't be placed within its neighbourhood, although
+    /// the bimap isn't full, as happens when too many keys have similar hashes. By default, the
+    /// bimap grows, as if it were full. See `ResizePolicy` for the alternatives.
+    ///
+    /// ```
+    /// # use isomorphism::{BiMap, BiMapBuilder, ResizePolicy};
+    /// let map: BiMap<String, String> = BiMapBuilder::new()
+    ///             .resize_policy(ResizePolicy::RedistributeFirst)
+    ///             .finish();
+    /// ```
+    pub fn resize_policy(self, resize_policy: ResizePolicy) -> Self {
+        BiMapBuilder {
+            resize_policy,
+            ..self
+        }
+    }
+
+    /// Makes the bimap replace its hashers with new ones, from `Default`, when too many keys keep
+    /// colliding with each other, rather than only growing. After a few resizes in a row that were
+    /// caused by a full neighbourhood rather than by the bimap being full, or once a key can't be
+    /// placed at all, every pair is moved into the same number of buckets with the new hashers.
+    /// For randomly seeded hashers such as `RandomState`, this breaks up clusters of keys that
+    /// only collide with the old seeds.
+    ///
+    /// This is on by default for the `RandomState` hashers that a builder starts with, and for
+    /// bimaps that create their own hashers from `Default`, such as through `collect`. Setting
+    /// either hasher with `left_hasher` or `right_hasher` turns it off, so for other hashers it
+    /// should be turned on after they have been set. As the hashers can be replaced at any insert,
+    /// hashes computed with the old ones, such as for `get_left_where`, won't find anything
+    /// afterwards. Hashers that ignore their seeds, or have none, can't be helped by this: see
+    /// `BiMap::insert` for what happens to keys they always give the same hash.
+    ///
+    /// ```
+    /// # use isomorphism::{BiMap, BiMapBuilder};
+    /// use std::collections::hash_map::RandomState;
+    ///
+    /// let map: BiMap<String, String> = BiMapBuilder::new()
+    ///             .left_hasher(RandomState::new())
+    ///             .right_hasher(RandomState::new())
+    ///             .reseed_on_collisions()
+    ///             .finish();
+    /// ```
+    pub fn reseed_on_collisions(self) -> Self
+    where
+        LH: Default,
+        RH: Default,
+    {
+        BiMapBuilder {
+            reseed: Some(fresh_hashers),
+            ..self
+        }
+    }
+
     /// Sets the hasher used for left values. By default, the hashmap will use the hashing
     /// algorithm used in the standard library hashmap, which is randomly generated and designed to
     /// be resistant to DoS attacks. Changing this hasher may lead to hash collisions and
@@ -69,6 +169,9 @@ impl<LH: BuildHasher, RH: BuildHasher, B: BitField> BiMapBuilder<LH, RH, B> {
     pub fn left_hasher<LH2: BuildHasher>(self, hasher: LH2) -> BiMapBuilder<LH2, RH, B> {
         BiMapBuilder {
             capacity: self.capacity,
+            ordered: self.ordered,
+            resize_policy: self.resize_policy,
+            reseed: None,
             left_hasher: hasher,
             right_hasher: self.right_hasher,
             bit_field: self.bit_field,
@@ -91,6 +194,9 @@ impl<LH: BuildHasher, RH: BuildHasher, B: BitField> BiMapBuilder<LH, RH, B> {
     pub fn right_hasher<RH2: BuildHasher>(self, hasher: RH2) -> BiMapBuilder<LH, RH2, B> {
         BiMapBuilder {
             capacity: self.capacity,
+            ordered: self.ordered,
+            resize_policy: self.resize_policy,
+            reseed: None,
             left_hasher: self.left_hasher,
             right_hasher: hasher,
             bit_field: self.bit_field,
@@ -113,6 +219,9 @@ impl<LH: BuildHasher, RH: BuildHasher, B: BitField> BiMapBuilder<LH, RH, B> {
     pub fn bitfield<B2: BitField>(self) -> BiMapBuilder<LH, RH, B2> {
         BiMapBuilder {
             capacity: self.capacity,
+            ordered: self.ordered,
+            resize_policy: self.resize_policy,
+            reseed: self.reseed,
             left_hasher: self.left_hasher,
             right_hasher: self.right_hasher,
             bit_field: PhantomData,
@@ -127,16 +236,44 @@ impl<LH: BuildHasher, RH: BuildHasher, B: BitField> BiMapBuilder<LH, RH, B> {
     /// let map: BiMap<String, String> = BiMapBuilder::new().finish();
     /// ```
     pub fn finish<L, R>(self) -> BiMap<L, R, LH, RH, B> {
-        let capacity = match self.capacity {
-            0 => 0,
-            cap => (cmp::max(DEFAULT_HASH_MAP_SIZE, cap) as f32 * MAX_LOAD_FACTOR).ceil() as usize,
-        };
+        // the hashmap can't function without any buckets, so small capacities are rounded up
+        let capacity = cmp::max(DEFAULT_HASH_MAP_SIZE, self.capacity);
+        let buckets = (capacity as f32 * MAX_LOAD_FACTOR).ceil() as usize;
+        self.finish_with_buckets(buckets)
+    }
+
+    /// Creates a new `BiMap` with the specified configurations, but with an exact number of
+    /// buckets, rather than the number of buckets implied by the builder's capacity. There must be
+    /// at least one bucket.
+    pub(crate) fn finish_with_buckets<L, R>(self, buckets: usize) -> BiMap<L, R, LH, RH, B> {
+        debug_assert!(buckets > 0, "a bimap needs at least one bucket");
         BiMap {
             len: 0,
-            left_data: Bucket::empty_vec(capacity),
-            right_data: Bucket::empty_vec(capacity),
+            left_data: Bucket::empty_vec(buckets),
+            old_left_data: Bucket::empty_vec(0),
+            incomplete: 0,
+            right_data: Bucket::empty_vec(buckets),
+            old_right_data: Bucket::empty_vec(0),
+            old_len: 0,
+            old_next: 0,
             left_hasher: self.left_hasher,
             right_hasher: self.right_hasher,
+            layout_generation: 0,
+            resizes: 0,
+            baseline_buckets: buckets,
+            modifications: 0,
+            order: if self.ordered {
+                Order::enabled(buckets)
+            } else {
+                Order::disabled()
+            },
+            lru_capacity: None,
+            resize_policy: self.resize_policy,
+            redistributing: false,
+            reseed: self.reseed,
+            collision_growths: 0,
+            reseeding: false,
+            on_resize: None,
         }
     }
 }
@@ -0,0 +1,133 @@
+//! Cursors that step through the pairs of a bimap.
+use bitfield::BitField;
+use guard::RightGuard;
+use BiMap;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::hash::{BuildHasher, Hash};
+
+/// A position within a `BiMap`, for exporting its pairs in pages with `export_page`. A cursor is
+/// small and can be copied, and with the `serde` feature it can be serialized, so it can be handed
+/// to a client between requests instead of being kept alongside the bimap.
+///
+/// The cursor records the buckets that have been exported so far, along with how many times the
+/// bimap had been modified when it was created. Once any pair has been inserted into or removed
+/// from the bimap, the rest of the pages could skip or repeat pairs, so the cursor is rejected.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ExportCursor {
+    /// The index of the next bucket to export from, within the left_data array.
+    pub(crate) position: usize,
+    /// The number of modifications the bimap had seen when the cursor was created.
+    pub(crate) generation: usize,
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for ExportCursor {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (self.position as u64, self.generation as u64).serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for ExportCursor {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (position, generation) = <(u64, u64)>::deserialize(deserializer)?;
+        Ok(ExportCursor {
+            position: position as usize,
+            generation: generation as usize,
+        })
+    }
+}
+
+/// A cursor over the pairs of a `BiMap`, in an arbitrary order, which can modify the right value
+/// of the pair it points at or remove that pair. This is returned by the `left_cursor_mut` method
+/// of `BiMap`, and is an alternative to `retain` for pruning the hashmap in a single pass.
+///
+/// Removing a pair never moves any of the others, so after `remove_current` the cursor points at
+/// the pair that `advance` would have moved it to, and no pair is skipped or visited twice.
+pub struct LeftCursorMut<'a, L: 'a, R: 'a, LH: 'a, RH: 'a, B: 'a> {
+    pub(crate) map: &'a mut BiMap<L, R, LH, RH, B>,
+    /// The index of the current left key within the left_data array, or the length of the array
+    /// once every pair has been visited.
+    pub(crate) index: usize,
+}
+
+impl<'a, L, R, LH, RH, B> LeftCursorMut<'a, L, R, LH, RH, B>
+where
+    L: Hash + Eq,
+    R: Hash + Eq,
+    LH: BuildHasher,
+    RH: BuildHasher,
+    B: BitField,
+{
+    /// Moves the cursor forwards to the next bucket with a pair in it, if the current one is empty
+    /// or holds an incomplete left key.
+    pub(crate) fn skip_empty(&mut self) {
+        let left_data = &self.map.left_data;
+        while self.index < left_data.len() && left_data[self.index].pair().is_none() {
+            self.index += 1;
+        }
+    }
+
+    /// Returns the pair the cursor points at, or None once every pair has been visited. As with
+    /// `borrow_right_mut`, the right value must not be modified in a way that changes its hash or
+    /// its equality with other values.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    ///
+    /// let mut cursor = map.left_cursor_mut();
+    /// let (left, right) = cursor.current().unwrap();
+    /// assert_eq!((&"Hello", 5), (left, *right));
+    /// ```
+    pub fn current(&mut self) -> Option<(&L, RightGuard<'_, R, RH>)> {
+        let &mut BiMap {
+            ref left_data,
+            ref mut right_data,
+            ref right_hasher,
+            ..
+        } = self.map;
+        let (left, right_index) = left_data.get(self.index)?.pair()?;
+        let &mut (ref mut right, ..) = right_data[right_index].data.as_mut().unwrap();
+        Some((left, RightGuard::new(right, right_hasher)))
+    }
+
+    /// Moves the cursor to the next pair. Does nothing once every pair has been visited.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    ///
+    /// let mut cursor = map.left_cursor_mut();
+    /// cursor.advance();
+    /// assert!(cursor.current().is_none());
+    /// ```
+    pub fn advance(&mut self) {
+        if self.index < self.map.left_data.len() {
+            self.index += 1;
+            self.skip_empty();
+        }
+    }
+
+    /// Removes the pair the cursor points at, and moves the cursor to the next pair. Returns the
+    /// removed pair, or None once every pair has been visited.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    ///
+    /// assert_eq!(Some(("Hello", 5)), map.left_cursor_mut().remove_current());
+    /// assert!(map.is_empty());
+    /// ```
+    pub fn remove_current(&mut self) -> Option<(L, R)> {
+        self.map.left_data.get(self.index)?.pair()?;
+        let pair = self.map.remove_at(self.index);
+        self.skip_empty();
+        Some(pair)
+    }
+}
@@ -0,0 +1,85 @@
+//! Removals that are collected while a bimap is borrowed, and applied to it afterwards.
+
+/// A set of keys to be removed from a `BiMap` at a later time. This allows the pairs that should
+/// be removed to be found while iterating over the bimap, which would otherwise conflict with the
+/// bimap being mutably borrowed to remove them. See the `apply_deferred` method of `BiMap`.
+#[derive(Clone, Debug)]
+pub struct DeferredRemovals<L, R> {
+    /// Keys to remove from the left of the bimap.
+    pub(crate) lefts: Vec<L>,
+    /// Keys to remove from the right of the bimap.
+    pub(crate) rights: Vec<R>,
+}
+
+impl<L, R> Default for DeferredRemovals<L, R> {
+    fn default() -> Self {
+        DeferredRemovals {
+            lefts: Vec::new(),
+            rights: Vec::new(),
+        }
+    }
+}
+
+impl<L, R> DeferredRemovals<L, R> {
+    /// Creates a new empty set of removals.
+    ///
+    /// ```
+    /// # use isomorphism::DeferredRemovals;
+    /// let removals: DeferredRemovals<u64, char> = DeferredRemovals::new();
+    /// assert!(removals.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Marks the pair with a given left key for removal.
+    ///
+    /// ```
+    /// # use isomorphism::DeferredRemovals;
+    /// let mut removals: DeferredRemovals<u64, char> = DeferredRemovals::new();
+    /// removals.remove_left(5);
+    /// assert_eq!(1, removals.len());
+    /// ```
+    pub fn remove_left(&mut self, left: L) {
+        self.lefts.push(left);
+    }
+
+    /// Marks the pair with a given right key for removal.
+    ///
+    /// ```
+    /// # use isomorphism::DeferredRemovals;
+    /// let mut removals: DeferredRemovals<u64, char> = DeferredRemovals::new();
+    /// removals.remove_right('a');
+    /// assert_eq!(1, removals.len());
+    /// ```
+    pub fn remove_right(&mut self, right: R) {
+        self.rights.push(right);
+    }
+
+    /// Returns the number of keys that have been marked for removal, from either side.
+    ///
+    /// ```
+    /// # use isomorphism::DeferredRemovals;
+    /// let mut removals = DeferredRemovals::new();
+    /// removals.remove_left(5);
+    /// removals.remove_right('a');
+    /// assert_eq!(2, removals.len());
+    /// ```
+    pub fn len(&self) -> usize {
+        self.lefts.len() + self.rights.len()
+    }
+
+    /// Returns true if no keys have been marked for removal.
+    ///
+    /// ```
+    /// # use isomorphism::DeferredRemovals;
+    /// let mut removals = DeferredRemovals::<u64, char>::new();
+    /// assert!(removals.is_empty());
+    ///
+    /// removals.remove_left(5);
+    /// assert!(!removals.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.lefts.is_empty() && self.rights.is_empty()
+    }
+}
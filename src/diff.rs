@@ -0,0 +1,76 @@
+//! The differences between the pairs of two bimaps.
+
+/// The changes that would turn the pairs of one `BiMap` into the pairs of another, as found by the
+/// `diff` method of `BiMap`. Pairs are listed in an arbitrary order.
+///
+/// A diff returned by `diff` borrows the keys of both bimaps, so `L` and `R` are references.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diff<L, R> {
+    /// The pairs of the other bimap whose left keys aren't in this one.
+    pub added: Vec<(L, R)>,
+    /// The pairs of this bimap whose left keys aren't in the other one.
+    pub removed: Vec<(L, R)>,
+    /// The left keys that are in both bimaps, but are paired with different right values, along
+    /// with the right value from this bimap and then the right value from the other one.
+    pub changed: Vec<(L, R, R)>,
+}
+
+impl<L, R> Default for Diff<L, R> {
+    fn default() -> Self {
+        Diff {
+            added: Vec::new(),
+            removed: Vec::new(),
+            changed: Vec::new(),
+        }
+    }
+}
+
+impl<L, R> Diff<L, R> {
+    /// Returns true if the diff has no changes in it, which means that the bimaps it was found
+    /// from hold the same pairs.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut first = BiMap::new();
+    /// first.insert("Hello", 5);
+    /// let mut second = BiMap::new();
+    /// second.insert("Hello", 5);
+    /// assert!(first.diff(&second).is_empty());
+    ///
+    /// second.insert("World", 7);
+    /// assert!(!first.diff(&second).is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl<'a, L: Clone, R: Clone> Diff<&'a L, &'a R> {
+    /// Clones the keys of a diff found by `diff`, so that it no longer borrows the bimaps it was
+    /// found from and can be given to `apply_diff`.
+    ///
+    /// ```
+    /// # use isomorphism::{BiMap, Diff};
+    /// let old: BiMap<&str, u64> = BiMap::new();
+    /// let mut new = BiMap::new();
+    /// new.insert("Hello", 5);
+    ///
+    /// let diff: Diff<&str, u64> = old.diff(&new).cloned();
+    /// assert_eq!(vec![("Hello", 5)], diff.added);
+    /// ```
+    pub fn cloned(&self) -> Diff<L, R> {
+        Diff {
+            added: self.added.iter().map(|&(left, right)| (left.clone(), right.clone())).collect(),
+            removed: self
+                .removed
+                .iter()
+                .map(|&(left, right)| (left.clone(), right.clone()))
+                .collect(),
+            changed: self
+                .changed
+                .iter()
+                .map(|&(left, old, new)| (left.clone(), old.clone(), new.clone()))
+                .collect(),
+        }
+    }
+}
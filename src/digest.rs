@@ -0,0 +1,108 @@
+//! The hasher used for the content digests of a bimap.
+use std::hash::Hasher;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// A 64 bit FNV-1a hasher, used for `content_hash` and `region_hashes` so that their digests stay
+/// the same across releases of Rust and of this crate.
+///
+/// Integers are always hashed as their little endian bytes, and `usize` and `isize` are widened to
+/// 64 bits first, so that the same keys give the same digest on every platform. The digest still
+/// depends on how the `Hash` implementations of the keys feed their data to the hasher.
+pub struct DigestHasher(u64);
+
+impl DigestHasher {
+    /// Creates a hasher that has not been given any data yet.
+    pub fn new() -> Self {
+        DigestHasher(FNV_OFFSET_BASIS)
+    }
+}
+
+impl Hasher for DigestHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ u64::from(byte)).wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    fn write_u8(&mut self, value: u8) {
+        self.write(&[value]);
+    }
+
+    fn write_u16(&mut self, value: u16) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_u32(&mut self, value: u32) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_u128(&mut self, value: u128) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_usize(&mut self, value: usize) {
+        self.write_u64(value as u64);
+    }
+
+    fn write_i8(&mut self, value: i8) {
+        self.write_u8(value as u8);
+    }
+
+    fn write_i16(&mut self, value: i16) {
+        self.write_u16(value as u16);
+    }
+
+    fn write_i32(&mut self, value: i32) {
+        self.write_u32(value as u32);
+    }
+
+    fn write_i64(&mut self, value: i64) {
+        self.write_u64(value as u64);
+    }
+
+    fn write_i128(&mut self, value: i128) {
+        self.write_u128(value as u128);
+    }
+
+    fn write_isize(&mut self, value: isize) {
+        self.write_i64(value as i64);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use digest::DigestHasher;
+    use std::hash::Hasher;
+
+    fn digest(bytes: &[u8]) -> u64 {
+        let mut hasher = DigestHasher::new();
+        hasher.write(bytes);
+        hasher.finish()
+    }
+
+    #[test]
+    fn matches_reference_fnv1a() {
+        assert_eq!(0xcbf2_9ce4_8422_2325, digest(b""));
+        assert_eq!(0xaf63_dc4c_8601_ec8c, digest(b"a"));
+        assert_eq!(0x8594_4171_f739_67e8, digest(b"foobar"));
+    }
+
+    #[test]
+    fn usize_hashed_as_u64() {
+        let mut narrow = DigestHasher::new();
+        narrow.write_usize(12345);
+        let mut wide = DigestHasher::new();
+        wide.write_u64(12345);
+        assert_eq!(wide.finish(), narrow.finish());
+    }
+}
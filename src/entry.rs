@@ -0,0 +1,265 @@
+//! Entries that give access to a single left key of a bimap, whether or not it is in it.
+use bitfield::BitField;
+use guard::RightGuard;
+use BiMap;
+
+use std::hash::{BuildHasher, Hash};
+
+/// A view into the pair holding a single left key of a `BiMap`, which may or may not exist. This
+/// is returned by the `entry_left` method of `BiMap`.
+pub enum LeftEntry<'a, L: 'a, R: 'a, LH: 'a, RH: 'a, B: 'a> {
+    /// The left key is paired with a right value.
+    Occupied(OccupiedLeftEntry<'a, L, R, LH, RH, B>),
+    /// The left key isn't paired with anything.
+    Vacant(VacantLeftEntry<'a, L, R, LH, RH, B>),
+}
+
+/// A view into a pair of a `BiMap`, found by its left key.
+pub struct OccupiedLeftEntry<'a, L: 'a, R: 'a, LH: 'a, RH: 'a, B: 'a> {
+    pub(crate) map: &'a mut BiMap<L, R, LH, RH, B>,
+    /// The index of the left key within the left_data array.
+    pub(crate) index: usize,
+}
+
+/// A view into the place in a `BiMap` where a left key that isn't paired with anything would go.
+pub struct VacantLeftEntry<'a, L: 'a, R: 'a, LH: 'a, RH: 'a, B: 'a> {
+    pub(crate) map: &'a mut BiMap<L, R, LH, RH, B>,
+    pub(crate) left: L,
+}
+
+impl<'a, L, R, LH, RH, B> LeftEntry<'a, L, R, LH, RH, B>
+where
+    L: Hash + Eq,
+    R: Hash + Eq,
+    LH: BuildHasher,
+    RH: BuildHasher,
+    B: BitField,
+{
+    /// Returns the left key of the entry.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map: BiMap<&str, u64> = BiMap::new();
+    /// assert_eq!(&"Hello", map.entry_left("Hello").left());
+    /// ```
+    pub fn left(&self) -> &L {
+        match *self {
+            LeftEntry::Occupied(ref entry) => entry.left(),
+            LeftEntry::Vacant(ref entry) => entry.left(),
+        }
+    }
+
+    /// Modifies the right value of the entry if the left key is paired with one, and does nothing
+    /// otherwise. As with `borrow_right_mut`, the right value must not be modified in a way that
+    /// changes its hash or its equality with other values.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    ///
+    /// // the left key isn't paired, so there is nothing to modify
+    /// map.entry_left("Hello").and_modify(|_| unreachable!()).or_insert(1);
+    /// assert_eq!(Some(&1), map.get_left("Hello"));
+    /// ```
+    pub fn and_modify<F: FnOnce(&mut R)>(self, f: F) -> Self {
+        match self {
+            LeftEntry::Occupied(mut entry) => {
+                f(&mut *entry.get_mut());
+                LeftEntry::Occupied(entry)
+            }
+            LeftEntry::Vacant(entry) => LeftEntry::Vacant(entry),
+        }
+    }
+
+    /// Pairs the left key with a given right value if it isn't already paired with one, and
+    /// returns the right value that the left key is paired with. The given right value replaces
+    /// any pair that it is already in, as it would with `insert`.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// assert_eq!(&5, map.entry_left("Hello").or_insert(5));
+    /// assert_eq!(&5, map.entry_left("Hello").or_insert(7));
+    /// ```
+    pub fn or_insert(self, default: R) -> &'a R {
+        self.or_insert_with(|| default)
+    }
+
+    /// Pairs the left key with the result of a function if it isn't already paired with anything,
+    /// and returns the right value that the left key is paired with. The function is only called
+    /// if the left key isn't paired.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    /// assert_eq!(&5, map.entry_left("Hello").or_insert_with(|| unreachable!()));
+    /// assert_eq!(&7, map.entry_left("World").or_insert_with(|| 7));
+    /// ```
+    pub fn or_insert_with<F: FnOnce() -> R>(self, default: F) -> &'a R {
+        match self {
+            LeftEntry::Occupied(entry) => entry.into_right(),
+            LeftEntry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+}
+
+impl<'a, L, R, LH, RH, B> OccupiedLeftEntry<'a, L, R, LH, RH, B>
+where
+    L: Hash + Eq,
+    R: Hash + Eq,
+    LH: BuildHasher,
+    RH: BuildHasher,
+    B: BitField,
+{
+    /// Returns the left key of the pair.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// # use isomorphism::LeftEntry;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    /// if let LeftEntry::Occupied(entry) = map.entry_left("Hello") {
+    ///     assert_eq!(&"Hello", entry.left());
+    /// }
+    /// ```
+    pub fn left(&self) -> &L {
+        let &(ref left, ..) = self.map.left_data[self.index].data.as_ref().unwrap();
+        left
+    }
+
+    /// Returns the right value of the pair.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// # use isomorphism::LeftEntry;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    /// if let LeftEntry::Occupied(entry) = map.entry_left("Hello") {
+    ///     assert_eq!(&5, entry.right());
+    /// }
+    /// ```
+    pub fn right(&self) -> &R {
+        let &(_, right_index, _) = self.map.left_data[self.index].data.as_ref().unwrap();
+        let &(ref right, ..) = self.map.right_data[right_index].data.as_ref().unwrap();
+        right
+    }
+
+    /// Gives mutable access to the right value of the pair, in the same way as
+    /// `borrow_right_mut`.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// # use isomorphism::LeftEntry;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    /// if let LeftEntry::Occupied(mut entry) = map.entry_left("Hello") {
+    ///     // a value that is no different leaves its hash the same
+    ///     *entry.get_mut() = 5;
+    /// }
+    /// assert_eq!(Some(&5), map.get_left("Hello"));
+    /// ```
+    pub fn get_mut(&mut self) -> RightGuard<'_, R, RH> {
+        let &mut BiMap {
+            ref left_data,
+            ref mut right_data,
+            ref right_hasher,
+            ..
+        } = self.map;
+        let &(_, right_index, _) = left_data[self.index].data.as_ref().unwrap();
+        let &mut (ref mut right, ..) = right_data[right_index].data.as_mut().unwrap();
+        RightGuard::new(right, right_hasher)
+    }
+
+    /// Converts the entry into a reference to the right value of the pair, which lives for as
+    /// long as the borrow of the hashmap.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// # use isomorphism::LeftEntry;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    /// if let LeftEntry::Occupied(entry) = map.entry_left("Hello") {
+    ///     assert_eq!(&5, entry.into_right());
+    /// }
+    /// ```
+    pub fn into_right(self) -> &'a R {
+        let map = self.map;
+        let &(_, right_index, _) = map.left_data[self.index].data.as_ref().unwrap();
+        let &(ref right, ..) = map.right_data[right_index].data.as_ref().unwrap();
+        right
+    }
+
+    /// Removes the pair from the hashmap, and returns it.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// # use isomorphism::LeftEntry;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    /// if let LeftEntry::Occupied(entry) = map.entry_left("Hello") {
+    ///     assert_eq!(("Hello", 5), entry.remove());
+    /// }
+    /// assert!(map.is_empty());
+    /// ```
+    pub fn remove(self) -> (L, R) {
+        self.map.remove_at(self.index)
+    }
+}
+
+impl<'a, L, R, LH, RH, B> VacantLeftEntry<'a, L, R, LH, RH, B>
+where
+    L: Hash + Eq,
+    R: Hash + Eq,
+    LH: BuildHasher,
+    RH: BuildHasher,
+    B: BitField,
+{
+    /// Returns the left key that would be inserted.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// # use isomorphism::LeftEntry;
+    /// let mut map: BiMap<&str, u64> = BiMap::new();
+    /// if let LeftEntry::Vacant(entry) = map.entry_left("Hello") {
+    ///     assert_eq!(&"Hello", entry.left());
+    /// }
+    /// ```
+    pub fn left(&self) -> &L {
+        &self.left
+    }
+
+    /// Takes back ownership of the left key.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// # use isomorphism::LeftEntry;
+    /// let mut map: BiMap<String, u64> = BiMap::new();
+    /// if let LeftEntry::Vacant(entry) = map.entry_left("Hello".to_owned()) {
+    ///     assert_eq!("Hello", entry.into_left());
+    /// }
+    /// ```
+    pub fn into_left(self) -> L {
+        self.left
+    }
+
+    /// Pairs the left key with a right value, and returns a reference to the right value. As with
+    /// `insert`, this replaces any pair that already holds the right value.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// # use isomorphism::LeftEntry;
+    /// let mut map = BiMap::new();
+    /// if let LeftEntry::Vacant(entry) = map.entry_left("Hello") {
+    ///     assert_eq!(&5, entry.insert(5));
+    /// }
+    /// assert_eq!(Some(&"Hello"), map.get_right(&5));
+    /// ```
+    pub fn insert(self, right: R) -> &'a R {
+        let map = self.map;
+        let index = map.insert_pair(self.left, right, true).index;
+        let &(_, right_index, _) = map.left_data[index].data.as_ref().unwrap();
+        let &(ref right, ..) = map.right_data[right_index].data.as_ref().unwrap();
+        right
+    }
+}
@@ -0,0 +1,103 @@
+//! Errors returned by the fallible operations on a bimap.
+use std::error::Error;
+use std::fmt;
+
+/// The error returned by `BiMap::try_reserve_exact` when the requested capacity could not be
+/// reserved.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TryReserveError {
+    /// The requested capacity is larger than the number of buckets that can ever be allocated.
+    CapacityOverflow,
+    /// The allocator was unable to provide the memory needed for the requested capacity.
+    AllocError,
+}
+
+impl fmt::Display for TryReserveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            TryReserveError::CapacityOverflow => {
+                f.write_str("capacity overflow while reserving space in the bimap")
+            }
+            TryReserveError::AllocError => {
+                f.write_str("memory allocation failed while reserving space in the bimap")
+            }
+        }
+    }
+}
+
+impl Error for TryReserveError {}
+
+/// The error returned by `BiMap::export_page` when the cursor can no longer be used.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ExportError {
+    /// The bimap has been modified since the cursor was created, so the pages that are left
+    /// wouldn't be consistent with the pages that have already been exported.
+    Modified,
+}
+
+impl fmt::Display for ExportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ExportError::Modified => {
+                f.write_str("the bimap was modified while its pairs were being exported")
+            }
+        }
+    }
+}
+
+impl Error for ExportError {}
+
+/// The error returned by `BiMap::try_from` when the pairs it was given don't form a one to one
+/// relation. Holds the first pair that shares a key with an earlier pair, along with its index.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InjectivityError<L, R> {
+    /// The left key of the pair is also the left key of an earlier pair.
+    DuplicateLeft {
+        /// The index of the pair within the input.
+        index: usize,
+        /// The pair itself.
+        pair: (L, R),
+    },
+    /// The right key of the pair is also the right key of an earlier pair.
+    DuplicateRight {
+        /// The index of the pair within the input.
+        index: usize,
+        /// The pair itself.
+        pair: (L, R),
+    },
+}
+
+impl<L, R> fmt::Display for InjectivityError<L, R> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            InjectivityError::DuplicateLeft { index, .. } => {
+                write!(f, "the pair at index {} repeats the left key of an earlier pair", index)
+            }
+            InjectivityError::DuplicateRight { index, .. } => {
+                write!(f, "the pair at index {} repeats the right key of an earlier pair", index)
+            }
+        }
+    }
+}
+
+impl<L: fmt::Debug, R: fmt::Debug> Error for InjectivityError<L, R> {}
+
+/// The error returned by `BiMap::resolve_left` when the left key isn't paired with anything.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ResolveError {
+    /// The bimap holds no pairs at all.
+    EmptyMap,
+    /// The bimap holds pairs, but none of them has the left key.
+    NotFound,
+}
+
+impl fmt::Display for ResolveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ResolveError::EmptyMap => f.write_str("the bimap is empty"),
+            ResolveError::NotFound => f.write_str("the key was not found in the bimap"),
+        }
+    }
+}
+
+impl Error for ResolveError {}
@@ -0,0 +1,78 @@
+//! Wrappers that let floating point numbers be used as keys of a bimap.
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
+
+/// An `f64` that can be hashed and compared for equality, so that it can be used as a key of a
+/// `BiMap`. A plain `f64` can't be, as NaN isn't equal to itself, and so a NaN key could never
+/// be found again.
+///
+/// Every NaN is equal to every other NaN, whatever its sign or payload, and `-0.0` is equal to
+/// `0.0`. All other values compare in the same way as `f64` does. Hashing is consistent with
+/// equality. For ordering, NaN is greater than every other value, including infinity.
+///
+/// ```
+/// # use isomorphism::BiMap;
+/// # use isomorphism::float::TotalF64;
+/// let mut map = BiMap::new();
+/// map.insert(TotalF64(f64::NAN), "not a number");
+/// assert_eq!(Some(&"not a number"), map.get_left(&TotalF64(-f64::NAN)));
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TotalF64(pub f64);
+
+impl TotalF64 {
+    /// Gives the same bits for every value that is equal to this one.
+    fn canonical_bits(self) -> u64 {
+        if self.0.is_nan() {
+            f64::NAN.to_bits()
+        } else if self.0 == 0.0 {
+            0
+        } else {
+            self.0.to_bits()
+        }
+    }
+}
+
+impl From<f64> for TotalF64 {
+    fn from(value: f64) -> Self {
+        TotalF64(value)
+    }
+}
+
+impl From<TotalF64> for f64 {
+    fn from(value: TotalF64) -> Self {
+        value.0
+    }
+}
+
+impl PartialEq for TotalF64 {
+    fn eq(&self, other: &Self) -> bool {
+        self.canonical_bits() == other.canonical_bits()
+    }
+}
+
+impl Eq for TotalF64 {}
+
+impl Hash for TotalF64 {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.canonical_bits().hash(state);
+    }
+}
+
+impl PartialOrd for TotalF64 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for TotalF64 {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.0.is_nan(), other.0.is_nan()) {
+            (true, true) => Ordering::Equal,
+            (true, false) => Ordering::Greater,
+            (false, true) => Ordering::Less,
+            // neither is NaN, and -0.0 and 0.0 compare as equal already
+            (false, false) => self.0.partial_cmp(&other.0).unwrap(),
+        }
+    }
+}
@@ -0,0 +1,60 @@
+//! Guards that give scoped mutable access to the keys of a bimap.
+use std::hash::{BuildHasher, Hash};
+use std::ops::{Deref, DerefMut};
+#[cfg(debug_assertions)]
+use std::thread;
+
+/// Mutable access to a right value within a `BiMap`, returned by `borrow_right_mut`.
+///
+/// As the hashmap finds right values by their hashes, the right value must not be modified in a
+/// way that changes its hash or its equality with other values. When debug assertions are
+/// enabled, the guard checks that the hash of the right value is the same when it is dropped as
+/// it was when the guard was created, and panics if it isn't. Changes to equality that don't
+/// also change the hash can't be detected.
+pub struct RightGuard<'a, R: Hash + 'a, RH: BuildHasher + 'a> {
+    /// The right value being mutated.
+    right: &'a mut R,
+    /// The hasher used for the right values of the hashmap.
+    #[cfg_attr(not(debug_assertions), allow(dead_code))]
+    right_hasher: &'a RH,
+    /// The hash of the right value when the guard was created.
+    #[cfg(debug_assertions)]
+    hash: u64,
+}
+
+impl<'a, R: Hash + 'a, RH: BuildHasher + 'a> RightGuard<'a, R, RH> {
+    /// Creates a guard over a right value of a hashmap.
+    pub(crate) fn new(right: &'a mut R, right_hasher: &'a RH) -> Self {
+        RightGuard {
+            #[cfg(debug_assertions)]
+            hash: right_hasher.hash_one(&*right),
+            right,
+            right_hasher,
+        }
+    }
+}
+
+impl<'a, R: Hash + 'a, RH: BuildHasher + 'a> Deref for RightGuard<'a, R, RH> {
+    type Target = R;
+
+    fn deref(&self) -> &R {
+        self.right
+    }
+}
+
+impl<'a, R: Hash + 'a, RH: BuildHasher + 'a> DerefMut for RightGuard<'a, R, RH> {
+    fn deref_mut(&mut self) -> &mut R {
+        self.right
+    }
+}
+
+impl<'a, R: Hash + 'a, RH: BuildHasher + 'a> Drop for RightGuard<'a, R, RH> {
+    fn drop(&mut self) {
+        // panicking while already panicking would abort, hiding the original panic
+        #[cfg(debug_assertions)]
+        assert!(
+            thread::panicking() || self.right_hasher.hash_one(&*self.right) == self.hash,
+            "a right value was modified in a way that changed its hash"
+        );
+    }
+}
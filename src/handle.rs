@@ -0,0 +1,22 @@
+//! Handles that refer to a pair in a bimap by its location, rather than by its keys.
+
+/// A lightweight reference to a pair within a `BiMap`, which can be used to remove that pair
+/// without hashing either of its keys. See the `handle_left` and `remove_by_handle` methods of
+/// `BiMap`.
+///
+/// A handle records the buckets that the pair was stored in, along with the generation of the
+/// hashmap's layout at the time. Any operation that moves existing pairs to different buckets,
+/// such as a resize, starts a new generation, after which the handle is rejected. The handle also
+/// records the generation of the pair's left bucket, which moves on whenever a key is stored
+/// there, so a handle to a removed pair never refers to a pair that later reuses its buckets.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Handle {
+    /// The index of the left key within the left_data array.
+    pub(crate) left_index: usize,
+    /// The index of the right key within the right_data array.
+    pub(crate) right_index: usize,
+    /// The layout generation of the hashmap when the handle was created.
+    pub(crate) generation: usize,
+    /// The generation of the bucket at left_index when the handle was created.
+    pub(crate) left_generation: usize,
+}
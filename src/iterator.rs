@@ -1,4 +1,4 @@
-use bucket::Bucket;
+use bucket::{Bucket, INCOMPLETE};
 
 use std::iter::Iterator;
 use std::slice;
@@ -12,18 +12,64 @@ where
 {
     left_data: slice::Iter<'a, Bucket<L, usize, B>>,
     right_data: &'a [Bucket<R, usize, B>],
+    /// The buckets of a shrink in progress, which are visited after the others.
+    old_left_data: slice::Iter<'a, Bucket<L, usize, B>>,
+    old_right_data: &'a [Bucket<R, usize, B>],
+    /// The number of pairs that haven't been returned yet.
+    remaining: usize,
 }
 
 impl<'a, L, R, B> Iter<'a, L, R, B> {
     pub fn new(
         left_data: slice::Iter<'a, Bucket<L, usize, B>>,
         right_data: &'a [Bucket<R, usize, B>],
+    ) -> Self {
+        let remaining = left_data
+            .as_slice()
+            .iter()
+            .filter(|bucket| bucket.pair().is_some())
+            .count();
+        Iter::with_len(left_data, right_data, remaining)
+    }
+
+    /// Creates an iterator over buckets that are known to hold `len` pairs, which saves counting
+    /// them.
+    pub(crate) fn with_len(
+        left_data: slice::Iter<'a, Bucket<L, usize, B>>,
+        right_data: &'a [Bucket<R, usize, B>],
+        len: usize,
+    ) -> Self {
+        Iter::shrinking(left_data, right_data, [].iter(), &[], len)
+    }
+
+    /// Creates an iterator over the buckets of a hashmap that is part way through a shrink, which
+    /// are known to hold `len` pairs between them.
+    pub(crate) fn shrinking(
+        left_data: slice::Iter<'a, Bucket<L, usize, B>>,
+        right_data: &'a [Bucket<R, usize, B>],
+        old_left_data: slice::Iter<'a, Bucket<L, usize, B>>,
+        old_right_data: &'a [Bucket<R, usize, B>],
+        len: usize,
     ) -> Self {
         Iter {
             left_data,
             right_data,
+            old_left_data,
+            old_right_data,
+            remaining: len,
         }
     }
+
+    /// Returns the next pair from one set of buckets.
+    fn next_pair(
+        left_data: &mut slice::Iter<'a, Bucket<L, usize, B>>,
+        right_data: &'a [Bucket<R, usize, B>],
+    ) -> Option<(&'a L, &'a R)> {
+        left_data
+            .filter_map(|bucket| bucket.pair())
+            .map(|(key, value)| (key, &right_data[value].data.as_ref().unwrap().0))
+            .next()
+    }
 }
 
 impl<'a, L, R, B> Iterator for Iter<'a, L, R, B>
@@ -37,18 +83,35 @@ where
         let &mut Iter {
             ref mut left_data,
             right_data,
+            ref mut old_left_data,
+            old_right_data,
+            ref mut remaining,
         } = self;
-        left_data
-            .filter_map(|bucket| bucket.data.as_ref())
-            .map(|&(ref key, value, _)| (key, &right_data[value].data.as_ref().unwrap().0))
-            .next()
+        let next = Self::next_pair(left_data, right_data)
+            .or_else(|| Self::next_pair(old_left_data, old_right_data));
+        if next.is_some() {
+            *remaining -= 1;
+        }
+        next
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
     }
 }
 
-/// An owning iterator over the pairs stored in a BiMap.
+impl<'a, L: 'a, R: 'a, B> ExactSizeIterator for Iter<'a, L, R, B> {}
+
+/// An owning iterator over the pairs stored in a BiMap. If it is dropped before it has returned
+/// every pair, all of the remaining left keys are dropped before any of the remaining right keys.
 pub struct IntoIter<L, R, B> {
+    // the left buckets must come before the right buckets, so that the left keys are dropped first
     left_data: Box<[Bucket<L, usize, B>]>,
+    old_left_data: Box<[Bucket<L, usize, B>]>,
     right_data: Box<[Bucket<R, usize, B>]>,
+    old_right_data: Box<[Bucket<R, usize, B>]>,
+    /// The index of the next bucket to look at, counting through left_data and then through
+    /// old_left_data.
     index: usize,
 }
 
@@ -56,10 +119,23 @@ impl<L, R, B> IntoIter<L, R, B> {
     pub(crate) fn new(
         left_data: Box<[Bucket<L, usize, B>]>,
         right_data: Box<[Bucket<R, usize, B>]>,
+    ) -> Self {
+        IntoIter::shrinking(left_data, right_data, Vec::new().into(), Vec::new().into())
+    }
+
+    /// Creates an iterator over the buckets of a hashmap that is part way through a shrink,
+    /// which yields the pairs in the old buckets after those in the new ones.
+    pub(crate) fn shrinking(
+        left_data: Box<[Bucket<L, usize, B>]>,
+        right_data: Box<[Bucket<R, usize, B>]>,
+        old_left_data: Box<[Bucket<L, usize, B>]>,
+        old_right_data: Box<[Bucket<R, usize, B>]>,
     ) -> Self {
         IntoIter {
             left_data,
+            old_left_data,
             right_data,
+            old_right_data,
             index: 0,
         }
     }
@@ -71,21 +147,28 @@ impl<L, R, B> Iterator for IntoIter<L, R, B> {
     fn next(&mut self) -> Option<Self::Item> {
         let &mut IntoIter {
             ref mut left_data,
+            ref mut old_left_data,
             ref mut right_data,
+            ref mut old_right_data,
             ref mut index,
         } = self;
 
         loop {
-            if *index >= left_data.len() {
+            let (left_data, right_data, bucket) = if *index < left_data.len() {
+                (&mut *left_data, &mut *right_data, *index)
+            } else if *index < left_data.len() + old_left_data.len() {
+                (&mut *old_left_data, &mut *old_right_data, *index - left_data.len())
+            } else {
                 break None;
-            }
-            if left_data[*index].data.is_some() {
-                let (left, right_index, ..) = left_data[*index].data.take().unwrap();
-                let (right, ..) = right_data[right_index].data.take().unwrap();
-                *index += 1;
-                break Some((left, right));
-            }
+            };
             *index += 1;
+            if let Some((left, right_index, ..)) = left_data[bucket].data.take() {
+                // an incomplete left key has no pair to return, so it is just dropped
+                if right_index != INCOMPLETE {
+                    let (right, ..) = right_data[right_index].data.take().unwrap();
+                    break Some((left, right));
+                }
+            }
         }
     }
 }
@@ -0,0 +1,237 @@
+//! A bimap whose right values are identified by a key extracted from them.
+use BiMap;
+use bitfield::{BitField, DefaultBitField};
+
+use std::borrow::Borrow;
+use std::collections::hash_map::RandomState;
+use std::fmt::{self, Debug};
+use std::hash::{BuildHasher, Hash, Hasher};
+
+/// A right value along with the key extracted from it. Hashing and equality only look at the key,
+/// so that right values can be looked up by their keys alone.
+#[derive(Debug)]
+struct Keyed<K, R> {
+    key: K,
+    value: R,
+}
+
+impl<K: PartialEq, R> PartialEq for Keyed<K, R> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl<K: Eq, R> Eq for Keyed<K, R> {}
+
+impl<K: Hash, R> Hash for Keyed<K, R> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key.hash(state);
+    }
+}
+
+impl<K, R> Borrow<K> for Keyed<K, R> {
+    fn borrow(&self) -> &K {
+        &self.key
+    }
+}
+
+/// A `BiMap` in which the right values are identified by a key that is extracted from each of
+/// them with a function, rather than by their own `Hash` and `Eq` implementations. Two right
+/// values with different keys are different right values, even if they compare as equal, and two
+/// right values with the same key are the same right value, even if they don't. The right values
+/// don't need to implement `Hash` or `Eq` at all.
+///
+/// The key of each right value is extracted once, as it is inserted, and is stored alongside it.
+///
+/// ```
+/// # use isomorphism::KeyedBiMap;
+/// let mut map = KeyedBiMap::with_right_key_fn(|&(id, _): &(u64, &str)| id);
+/// map.insert("Hello", (1, "greeting"));
+/// map.insert("World", (2, "greeting"));
+///
+/// assert_eq!(Some(&"World"), map.get_right(&(2, "anything")));
+/// assert_eq!(Some(&"Hello"), map.get_right_key(&1));
+/// ```
+pub struct KeyedBiMap<
+    L,
+    R,
+    K,
+    F,
+    LH = RandomState,
+    RH = RandomState,
+    B = DefaultBitField,
+> {
+    inner: BiMap<L, Keyed<K, R>, LH, RH, B>,
+    key_fn: F,
+}
+
+impl<L, R, K, F> KeyedBiMap<L, R, K, F>
+where
+    F: Fn(&R) -> K,
+{
+    /// Creates a new empty KeyedBiMap, which identifies its right values by the keys that `key_fn`
+    /// returns for them.
+    ///
+    /// ```
+    /// # use isomorphism::KeyedBiMap;
+    /// let map: KeyedBiMap<u64, String, usize, _> = KeyedBiMap::with_right_key_fn(String::len);
+    /// ```
+    pub fn with_right_key_fn(key_fn: F) -> Self {
+        KeyedBiMap {
+            inner: BiMap::new(),
+            key_fn,
+        }
+    }
+}
+
+impl<L, R, K, F, LH, RH, B> KeyedBiMap<L, R, K, F, LH, RH, B> {
+    /// Returns the number of pairs in the hashmap.
+    ///
+    /// ```
+    /// # use isomorphism::KeyedBiMap;
+    /// let mut map = KeyedBiMap::with_right_key_fn(|&right: &u64| right);
+    /// map.insert("Hello", 5);
+    /// assert_eq!(1, map.len());
+    /// ```
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns true if the hashmap holds no pairs.
+    ///
+    /// ```
+    /// # use isomorphism::KeyedBiMap;
+    /// let map: KeyedBiMap<&str, u64, u64, _> = KeyedBiMap::with_right_key_fn(|&right| right);
+    /// assert!(map.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// An iterator visiting all pairs in an arbitrary order.
+    ///
+    /// ```
+    /// # use isomorphism::KeyedBiMap;
+    /// let mut map = KeyedBiMap::with_right_key_fn(|&right: &u64| right);
+    /// map.insert("Hello", 5);
+    ///
+    /// let pairs: Vec<_> = map.iter().collect();
+    /// assert_eq!(vec![(&"Hello", &5)], pairs);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = (&L, &R)> {
+        self.inner.iter().map(|(left, right)| (left, &right.value))
+    }
+}
+
+impl<L, R, K, F, LH, RH, B> KeyedBiMap<L, R, K, F, LH, RH, B>
+where
+    L: Hash + Eq,
+    K: Hash + Eq,
+    F: Fn(&R) -> K,
+    LH: BuildHasher,
+    RH: BuildHasher,
+    B: BitField,
+{
+    /// Inserts an (L, R) pair into the hashmap, removing any pair that holds the L or a right
+    /// value with the same key as the R, in the same way as `BiMap::insert`. Returns the right
+    /// value that was paired with the L and the left value that was paired with the R's key, if
+    /// there were any.
+    ///
+    /// ```
+    /// # use isomorphism::KeyedBiMap;
+    /// let mut map = KeyedBiMap::with_right_key_fn(|right: &String| right.to_lowercase());
+    /// map.insert("Hello", String::from("greeting"));
+    ///
+    /// let (old_right, old_left) = map.insert("World", String::from("GREETING"));
+    /// assert_eq!(None, old_right);
+    /// assert_eq!(Some("Hello"), old_left);
+    /// ```
+    pub fn insert(&mut self, left: L, right: R) -> (Option<R>, Option<L>) {
+        let key = (self.key_fn)(&right);
+        let (old_right, old_left) = self.inner.insert(left, Keyed { key, value: right });
+        (old_right.map(|right| right.value), old_left)
+    }
+
+    /// Gets the right value paired with a left key, if there is one.
+    ///
+    /// ```
+    /// # use isomorphism::KeyedBiMap;
+    /// let mut map = KeyedBiMap::with_right_key_fn(|&right: &u64| right);
+    /// map.insert("Hello", 5);
+    /// assert_eq!(Some(&5), map.get_left("Hello"));
+    /// ```
+    pub fn get_left<Q: ?Sized>(&self, left: &Q) -> Option<&R>
+    where
+        L: Borrow<Q>,
+        Q: Hash + Eq,
+    {
+        self.inner.get_left(left).map(|right| &right.value)
+    }
+
+    /// Gets the left value paired with the right value that has the same key as a given right
+    /// value, if there is one.
+    ///
+    /// ```
+    /// # use isomorphism::KeyedBiMap;
+    /// let mut map = KeyedBiMap::with_right_key_fn(|&right: &u64| right % 10);
+    /// map.insert("Hello", 5);
+    /// assert_eq!(Some(&"Hello"), map.get_right(&15));
+    /// ```
+    pub fn get_right(&self, right: &R) -> Option<&L> {
+        self.get_right_key(&(self.key_fn)(right))
+    }
+
+    /// Gets the left value paired with the right value that has a given key, if there is one.
+    ///
+    /// ```
+    /// # use isomorphism::KeyedBiMap;
+    /// let mut map = KeyedBiMap::with_right_key_fn(|&right: &u64| right % 10);
+    /// map.insert("Hello", 5);
+    /// assert_eq!(Some(&"Hello"), map.get_right_key(&5));
+    /// ```
+    pub fn get_right_key(&self, key: &K) -> Option<&L> {
+        self.inner.get_right(key)
+    }
+
+    /// Removes the pair holding a left key, returning the right value it was paired with, if
+    /// there was one.
+    ///
+    /// ```
+    /// # use isomorphism::KeyedBiMap;
+    /// let mut map = KeyedBiMap::with_right_key_fn(|&right: &u64| right);
+    /// map.insert("Hello", 5);
+    /// assert_eq!(Some(5), map.remove_left("Hello"));
+    /// assert!(map.is_empty());
+    /// ```
+    pub fn remove_left<Q: ?Sized>(&mut self, left: &Q) -> Option<R>
+    where
+        L: Borrow<Q>,
+        Q: Hash + Eq,
+    {
+        self.inner.remove_left(left).map(|right| right.value)
+    }
+
+    /// Removes the pair holding a right value with a given key, returning the left value it was
+    /// paired with, if there was one.
+    ///
+    /// ```
+    /// # use isomorphism::KeyedBiMap;
+    /// let mut map = KeyedBiMap::with_right_key_fn(|&right: &u64| right);
+    /// map.insert("Hello", 5);
+    /// assert_eq!(Some("Hello"), map.remove_right_key(&5));
+    /// assert!(map.is_empty());
+    /// ```
+    pub fn remove_right_key(&mut self, key: &K) -> Option<L> {
+        self.inner.remove_right(key)
+    }
+}
+
+impl<L, R, K, F, LH, RH, B> Debug for KeyedBiMap<L, R, K, F, LH, RH, B>
+where
+    L: Debug,
+    R: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
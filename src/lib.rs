@@ -8,54 +8,204 @@
 //! key must be hashable and comparable, and that duplicate right keys cannot exist within the
 //! bimap, even if they have different left keys mapping to them.
 
+// The crate destructures through references explicitly (`let &BiMap { ref .. } = self`) and
+// keeps `?Sized` in the generic list alongside where clauses, in the style of the standard library
+// collections.
+#![allow(clippy::needless_borrowed_reference, clippy::multiple_bound_locations)]
+
 #[cfg(test)]
 #[macro_use]
 extern crate quickcheck;
 
+#[cfg(feature = "rayon")]
+extern crate rayon;
+#[cfg(feature = "rayon")]
+use rayon::iter::{FlatMapIter, IntoParallelIterator, ParallelIterator};
+
 #[cfg(feature = "serde")]
 extern crate serde;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
+// the hashers shared with the integration tests, not all of which the unit tests use
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../tests/common/mod.rs"]
+mod common;
+
 pub mod bitfield;
 mod bucket;
 mod builder;
+mod cursor;
+mod deferred;
+mod diff;
+mod digest;
+mod entry;
+mod error;
+pub mod float;
+mod guard;
+mod handle;
 mod iterator;
+mod keyed;
+mod order;
+mod retain;
+mod strict;
+mod timestamped;
 
 use bitfield::{BitField, DefaultBitField};
-use bucket::Bucket;
-pub use builder::BiMapBuilder;
+use bucket::{Bucket, INCOMPLETE};
+pub use builder::{BiMapBuilder, ResizePolicy};
+pub use cursor::{ExportCursor, LeftCursorMut};
+pub use deferred::DeferredRemovals;
+pub use diff::Diff;
+use digest::DigestHasher;
+pub use entry::{LeftEntry, OccupiedLeftEntry, VacantLeftEntry};
+pub use error::{ExportError, InjectivityError, ResolveError, TryReserveError};
+pub use guard::RightGuard;
+pub use handle::Handle;
 pub use iterator::{IntoIter, Iter};
+pub use keyed::KeyedBiMap;
+pub use retain::RetainControl;
+pub use strict::{OverwriteBiMap, StrictBiMap};
+pub use timestamped::TimestampedBiMap;
+use order::Order;
 
 use std::borrow::Borrow;
+use std::cmp::{self, Ordering};
+use std::collections::HashMap;
 use std::collections::hash_map::RandomState;
+#[cfg(feature = "rayon")]
+use std::convert;
+use std::convert::TryFrom;
 use std::fmt::{self, Debug};
 use std::hash::{BuildHasher, Hash, Hasher};
 use std::iter::{self, Extend, FromIterator};
 use std::mem;
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+#[cfg(feature = "debug-deterministic")]
+use std::sync::OnceLock;
 
 pub(crate) const DEFAULT_HASH_MAP_SIZE: usize = 32;
+/// The number of resizes in a row caused by full neighbourhoods after which the hashers are
+/// replaced, if the hashmap reseeds on collisions.
+const RESEED_AFTER_GROWTHS: usize = 3;
 const RESIZE_GROWTH_FACTOR: usize = 2;
 
 // left as a fraction to avoid floating point multiplication and division where it isn't needed
 pub(crate) const MAX_LOAD_FACTOR: f32 = 1.1;
 
+/// The fraction of the buckets needed to stay under the maximum load factor that `reserve` adds
+/// on top, as room for keys that cluster in the same neighbourhoods.
+const RESERVE_MARGIN_DIVISOR: usize = 4;
+
+/// Creates a hash builder for a bimap that wasn't given one, as by `new`, `Default` or
+/// `BiMapBuilder::new`. This is a newly seeded `RandomState`.
+#[cfg(not(feature = "debug-deterministic"))]
+pub(crate) fn default_hasher() -> RandomState {
+    RandomState::new()
+}
+
+/// Creates a hash builder for a bimap that wasn't given one, as by `new`, `Default` or
+/// `BiMapBuilder::new`. As the `debug-deterministic` feature is enabled, this is always a copy of
+/// the same `RandomState`, so bimaps that have the same pairs inserted in the same order iterate in
+/// the same order as each other. The standard library has no way to fix the seed of a
+/// `RandomState`, so the order can still differ from one run of a program to the next.
+#[cfg(feature = "debug-deterministic")]
+pub(crate) fn default_hasher() -> RandomState {
+    static SHARED: OnceLock<RandomState> = OnceLock::new();
+    SHARED.get_or_init(RandomState::new).clone()
+}
+
+/// A callback that is told about each resize of a bimap. It must be `Send` and `Sync` so that the
+/// bimap holding it still is.
+type ResizeCallback = dyn FnMut(usize, usize) + Send + Sync;
+
+/// The buckets holding one side of the pairs of a bimap, each of which also holds the index of
+/// the bucket on the other side that its pair is in.
+type Buckets<K, B> = [Bucket<K, usize, B>];
+
 /// The two way hashmap itself. See the crate level documentation for more information. Uses
 /// hopscotch hashing internally.
 ///
 /// L and R are the left and right types being mapped to eachother. LH and RH are the hash builders
 /// used to hash the left keys and right keys. B is the bitfield used to store neighbourhoods.
+///
+/// Whenever the hashmap drops many of its keys at once - when it is dropped, cleared with `clear`,
+/// or when an iterator from `into_iter` or `drain` is dropped before it is finished - all of the
+/// left keys are dropped before any of the right keys. This makes it easier to reason about the
+/// cleanup of keys that refer to each other, such as through `Rc` and `Weak`.
 pub struct BiMap<L, R, LH = RandomState, RH = RandomState, B = DefaultBitField> {
-    /// The number of pairs inside the map
+    /// The number of left keys inside the map, including incomplete ones, not counting those in
+    /// old_left_data
     len: usize,
+    // the order of the fields that hold keys is the order in which they get dropped, so all of
+    // the left keys must come before all of the right keys
     /// All of the left keys, and the locations of their pairs within the right_data array.
     left_data: Box<[Bucket<L, usize, B>]>,
+    /// While a shrink started by `shrink_incremental` is in progress, the left keys of the pairs
+    /// that haven't been moved into the smaller buckets yet. Empty the rest of the time.
+    old_left_data: Box<[Bucket<L, usize, B>]>,
+    /// The number of left keys that have been inserted without a right key to pair them with,
+    /// in either set of buckets. These are stored among the other left keys, with a cross-index
+    /// of INCOMPLETE.
+    incomplete: usize,
     /// All of the right keys, and the locations of their pairs within the left_data array.
     right_data: Box<[Bucket<R, usize, B>]>,
+    /// The right keys of the pairs that haven't been moved yet by a shrink in progress.
+    old_right_data: Box<[Bucket<R, usize, B>]>,
+    /// The number of left keys that haven't been moved yet by a shrink in progress, including
+    /// incomplete ones.
+    old_len: usize,
+    /// The index of the next bucket in old_left_data to move pairs out of. Every bucket before it
+    /// has already been emptied.
+    old_next: usize,
     /// Used to generate hash values for the left keys
     left_hasher: LH,
     /// Used to generate hash values for the right keys
     right_hasher: RH,
+    /// Incremented whenever pairs that are already in the hashmap are moved to different
+    /// buckets, so that any handles to the old buckets can be rejected.
+    layout_generation: usize,
+    /// The number of times the buckets have been replaced with a set of buckets of another size.
+    resizes: usize,
+    /// The number of buckets that the hashmap was created with.
+    baseline_buckets: usize,
+    /// Incremented whenever a pair is inserted into or removed from the hashmap, or moved to
+    /// another bucket, so that any export cursors can be rejected.
+    modifications: usize,
+    /// The order in which the pairs were inserted, if it is being tracked.
+    order: Order,
+    /// The maximum number of pairs to keep before evicting the oldest, if any.
+    lru_capacity: Option<usize>,
+    /// What to do when a pair can't be placed in its neighbourhood.
+    resize_policy: ResizePolicy,
+    /// Set while the pairs are being redistributed, so that a failure to place a pair during the
+    /// redistribution grows the hashmap instead of redistributing again.
+    redistributing: bool,
+    /// Creates new hashers to replace the current ones when keys keep colliding, if the hashmap
+    /// should do so.
+    reseed: Option<fn() -> (LH, RH)>,
+    /// The number of resizes in a row that were caused by a full neighbourhood, rather than by the
+    /// hashmap being full.
+    collision_growths: usize,
+    /// Set while the pairs are being moved to the buckets given by new hashers, so that a failure
+    /// to place a pair while doing so grows the hashmap instead of reseeding again.
+    reseeding: bool,
+    /// Called with the old and new number of buckets whenever the hashmap is resized.
+    on_resize: Option<Box<ResizeCallback>>,
+}
+
+/// The result of inserting a pair into a bimap.
+struct Inserted<L, R> {
+    /// The index of the inserted left key within the left_data array.
+    index: usize,
+    /// The right value that was previously associated with the inserted left key.
+    old_right: Option<R>,
+    /// The left value that was previously associated with the inserted right key.
+    old_left: Option<L>,
+    /// The pair that was evicted to keep the hashmap within its LRU capacity.
+    evicted: Option<(L, R)>,
 }
 
 impl<L, R> Default for BiMap<L, R> {
@@ -74,6 +224,85 @@ impl<L, R> BiMap<L, R> {
     pub fn new() -> Self {
         Default::default()
     }
+
+    /// Creates a new empty BiMap, with space for at least `capacity` pairs. See the `capacity`
+    /// method of `BiMapBuilder` for more information.
+    ///
+    /// The buckets start out empty, but the allocator may hand out zeroed memory that the
+    /// operating system only faults in on first use, so the first inserts can be slower than
+    /// later ones. See `with_capacity_prefaulted` to avoid this.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let map: BiMap<u64, char> = BiMap::with_capacity(1024);
+    /// assert!(map.capacity() >= 1024);
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Self {
+        BiMapBuilder::new().capacity(capacity).finish()
+    }
+
+    /// Creates a new empty BiMap in the same way as `with_capacity`, but also writes to every
+    /// bucket before returning it, so that all of its memory has already been faulted in. The
+    /// first inserts into the hashmap then don't pay for page faults, at the cost of creating it
+    /// more slowly. This is intended for services that care about their worst case latency.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map: BiMap<u64, char> = BiMap::with_capacity_prefaulted(1024);
+    /// assert!(map.capacity() >= 1024);
+    ///
+    /// map.insert(1, 'a');
+    /// assert_eq!(Some(&'a'), map.get_left(&1));
+    /// ```
+    pub fn with_capacity_prefaulted(capacity: usize) -> Self {
+        let mut map = Self::with_capacity(capacity);
+        Bucket::prefault(&mut map.left_data);
+        Bucket::prefault(&mut map.right_data);
+        map
+    }
+
+    /// Creates a new empty BiMap, with space for at least `capacity` pairs. Unlike
+    /// `with_capacity`, small capacities are not rounded up to a minimum size, so this can be used
+    /// to create hashmaps that are as small as possible.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// use std::num::NonZeroUsize;
+    ///
+    /// let mut map: BiMap<u64, char> = BiMap::with_capacity_nonzero(NonZeroUsize::new(1).unwrap());
+    /// map.insert(1, 'a');
+    /// assert_eq!(Some(&'a'), map.get_left(&1));
+    /// ```
+    pub fn with_capacity_nonzero(capacity: NonZeroUsize) -> Self {
+        let buckets = (capacity.get() as f32 * MAX_LOAD_FACTOR).ceil() as usize;
+        BiMapBuilder::new().finish_with_buckets(buckets)
+    }
+
+    /// Creates a new empty BiMap that holds at most `lru_capacity` pairs, as a bidirectional LRU
+    /// cache. Inserting a new pair into a full hashmap evicts the pair that was inserted the
+    /// longest time ago: see `insert_evicting`. Reinserting a pair counts as inserting it again,
+    /// but looking it up does not. The hashmap keeps track of its insertion order, as if built
+    /// with the `ordered` method of `BiMapBuilder`.
+    ///
+    /// Panics if `lru_capacity` is 0.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::with_lru_capacity(2);
+    /// map.insert("Hello", 5);
+    /// map.insert("World", 7);
+    /// map.insert("Hashmaps", 9);
+    ///
+    /// assert_eq!(2, map.len());
+    /// assert_eq!(None, map.get_left("Hello"));
+    /// assert_eq!(None, map.get_right(&5));
+    /// ```
+    pub fn with_lru_capacity(lru_capacity: usize) -> Self {
+        assert!(lru_capacity > 0, "an LRU bimap must be able to hold at least one pair");
+        let mut map: Self = BiMapBuilder::new().capacity(lru_capacity).ordered().finish();
+        map.lru_capacity = Some(lru_capacity);
+        map
+    }
 }
 
 impl<L, R, LH, RH, B> BiMap<L, R, LH, RH, B> {
@@ -90,6 +319,65 @@ impl<L, R, LH, RH, B> BiMap<L, R, LH, RH, B> {
         (self.left_data.len() as f32 / MAX_LOAD_FACTOR).floor() as usize
     }
 
+    /// Returns the hasher used for the left keys of the hashmap. The hashers are replaced by
+    /// `clear_reseed`, and by inserts into a hashmap that reseeds on collisions, as described by
+    /// the `reseed_on_collisions` method of `BiMapBuilder`, after which hashes from the old hasher
+    /// are no use.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// use std::hash::BuildHasher;
+    ///
+    /// let map: BiMap<u64, char> = BiMap::new();
+    /// let hash = map.left_hasher().hash_one(&5);
+    /// assert_eq!(hash, map.left_hasher().hash_one(&5));
+    /// ```
+    pub fn left_hasher(&self) -> &LH {
+        &self.left_hasher
+    }
+
+    /// Registers a callback to be called whenever the hashmap is resized, replacing any callback
+    /// that was registered before. The callback is given the number of buckets before and after
+    /// the resize, after every pair has been moved into the new buckets. This covers every
+    /// resize, whether it grows the hashmap as pairs are inserted, or is asked for through a
+    /// method such as `reserve` or `shrink_to_fit`.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let resizes = Arc::new(Mutex::new(Vec::new()));
+    /// let mut map = BiMap::new();
+    /// let log = resizes.clone();
+    /// map.on_resize(Box::new(move |old, new| log.lock().unwrap().push((old, new))));
+    ///
+    /// for i in 0..100 {
+    ///     map.insert(i, i);
+    /// }
+    /// assert!(!resizes.lock().unwrap().is_empty());
+    /// ```
+    pub fn on_resize(&mut self, callback: Box<dyn FnMut(usize, usize) + Send + Sync>) {
+        self.on_resize = Some(callback);
+    }
+
+    /// Returns approximately how many bytes of heap memory the hashmap is using for its buckets
+    /// and bookkeeping. This doesn't include any heap memory owned by the keys themselves, such as
+    /// the contents of `String` keys.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let small: BiMap<u64, char> = BiMap::with_capacity(10);
+    /// let large: BiMap<u64, char> = BiMap::with_capacity(1000);
+    /// assert!(small.memory_usage() < large.memory_usage());
+    /// ```
+    pub fn memory_usage(&self) -> usize {
+        mem::size_of_val(&*self.left_data)
+            + mem::size_of_val(&*self.right_data)
+            + mem::size_of_val(&*self.old_left_data)
+            + mem::size_of_val(&*self.old_right_data)
+            + self.order.memory_usage()
+    }
+
     /// Returns the number of pairs inside this hashmap. Each remove will decrement this count.
     /// Each insert will increment this count, but may then also decrement it by one or two if the
     /// keys being inserted already existed and were associated with other pairs.
@@ -109,7 +397,7 @@ impl<L, R, LH, RH, B> BiMap<L, R, LH, RH, B> {
     /// assert_eq!(1, map.len());
     /// ```
     pub fn len(&self) -> usize {
-        self.len
+        self.len + self.old_len - self.incomplete
     }
 
     /// Returns true if the bimap contains no pairs.
@@ -123,7 +411,7 @@ impl<L, R, LH, RH, B> BiMap<L, R, LH, RH, B> {
     /// assert!(!map.is_empty());
     /// ```
     pub fn is_empty(&self) -> bool {
-        self.len == 0
+        self.len() == 0
     }
 
     /// An iterator visiting all key-value pairs in an arbitrary order. The iterator element is
@@ -139,446 +427,2904 @@ impl<L, R, LH, RH, B> BiMap<L, R, LH, RH, B> {
     ///     println!("{} {}", left, right);
     /// }
     /// ```
-    pub fn iter(&self) -> Iter<L, R, B> {
+    pub fn iter(&self) -> Iter<'_, L, R, B> {
         self.into_iter()
     }
-}
 
-impl<L, R, LH, RH, B> BiMap<L, R, LH, RH, B>
-where
-    L: Hash + Eq,
-    R: Hash + Eq,
-    LH: BuildHasher,
-    RH: BuildHasher,
-    B: BitField,
-{
-    /// Finds the ideal position of a key within the hashmap.
-    fn find_ideal_index<K: Hash, H: BuildHasher>(key: &K, hasher: &H, len: usize) -> usize {
-        let mut hasher = hasher.build_hasher();
-        key.hash(&mut hasher);
-        hasher.finish() as usize % len
+    /// Creates a cursor at the start of the hashmap, for exporting its pairs a page at a time
+    /// with `export_page`.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    ///
+    /// let mut cursor = map.export_cursor();
+    /// assert_eq!(Ok(vec![(&"Hello", &5)]), map.export_page(&mut cursor, 10));
+    /// ```
+    pub fn export_cursor(&self) -> ExportCursor {
+        ExportCursor {
+            position: 0,
+            generation: self.modifications,
+        }
     }
 
-    /// Find the bitfield associated with an ideal hash index in a hashmap array, and mark a given
-    /// index as full.
-    fn mark_as_full<K>(ideal_index: usize, actual_index: usize, data: &mut [Bucket<K, usize, B>]) {
-        let offset = (data.len() + actual_index - ideal_index) % data.len();
-        data[ideal_index].neighbourhood = data[ideal_index].neighbourhood | B::one_at(offset);
+    /// Returns up to `n` pairs from where a cursor is, in an arbitrary but consistent order, and
+    /// moves the cursor past them. Once every pair has been returned, the pages are empty. Each
+    /// pair is returned once over all of the pages, as long as the hashmap isn't modified in
+    /// between. If a pair has been inserted or removed since the cursor was created, an error is
+    /// returned instead, and the export has to start again from a new cursor.
+    ///
+    /// ```
+    /// # use isomorphism::{BiMap, ExportError};
+    /// let mut map: BiMap<u64, u64> = (0..10).map(|i| (i, i)).collect();
+    ///
+    /// let mut cursor = map.export_cursor();
+    /// let mut pairs = Vec::new();
+    /// loop {
+    ///     let page = map.export_page(&mut cursor, 3).unwrap();
+    ///     if page.is_empty() {
+    ///         break;
+    ///     }
+    ///     pairs.extend(page);
+    /// }
+    /// assert_eq!(10, pairs.len());
+    ///
+    /// let mut cursor = map.export_cursor();
+    /// map.insert(10, 10);
+    /// assert_eq!(Err(ExportError::Modified), map.export_page(&mut cursor, 3));
+    /// ```
+    pub fn export_page(
+        &self,
+        cursor: &mut ExportCursor,
+        n: usize,
+    ) -> Result<Vec<(&L, &R)>, ExportError> {
+        if cursor.generation != self.modifications {
+            return Err(ExportError::Modified);
+        }
+
+        let buckets = self.left_data.len() + self.old_left_data.len();
+        let mut page = Vec::new();
+        while page.len() < n && cursor.position < buckets {
+            let (left_data, right_data, index) = self.buckets_at(cursor.position);
+            if let Some((left, right_index)) = left_data[index].pair() {
+                let &(ref right, ..) = right_data[right_index].data.as_ref().unwrap();
+                page.push((left, right));
+            }
+            cursor.position += 1;
+        }
+        Ok(page)
     }
 
-    /// Finds the bitflield associated with an ideal hash index in a hashmap array, and mark a
-    /// given index as empty.
-    fn mark_as_empty<K>(ideal_index: usize, actual_index: usize, data: &mut [Bucket<K, usize, B>]) {
-        let offset = (data.len() + actual_index - ideal_index) % data.len();
-        data[ideal_index].neighbourhood = data[ideal_index].neighbourhood & B::zero_at(offset);
+    /// An iterator visiting all key-value pairs in an arbitrary order, along with the index at
+    /// which each right value is stored inside the hashmap. The indices are unique, and are all
+    /// less than the number of buckets in the hashmap (counting the buckets being shrunk, while
+    /// `shrink_incremental` is part way through), so they can be used to index into other data
+    /// structures, and to look pairs back up with `pair_at_right_index`. The indices are only
+    /// stable until the next time that the hashmap is modified, as any insert or remove can move
+    /// pairs around.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    /// map.insert("World", 7);
+    ///
+    /// for (&left, &right, index) in map.iter_with_right_index() {
+    ///     assert_eq!(Some((&left, &right)), map.pair_at_right_index(index));
+    /// }
+    /// ```
+    pub fn iter_with_right_index(&self) -> impl Iterator<Item = (&L, &R, usize)> {
+        // the right values of a shrink in progress are numbered after the others
+        let buckets = self.right_data.len();
+        iter::once((&self.left_data, &self.right_data, 0))
+            .chain(iter::once((&self.old_left_data, &self.old_right_data, buckets)))
+            .flat_map(|(left_data, right_data, first_index)| {
+                left_data
+                    .iter()
+                    .filter_map(|bucket| bucket.pair())
+                    .map(move |(left, right_index)| {
+                        let &(ref right, ..) = right_data[right_index].data.as_ref().unwrap();
+                        (left, right, first_index + right_index)
+                    })
+            })
     }
 
-    /// Inserts a given key into its data bucket. As this may do reshuffling, it requires a
-    /// reference to the value data buckets also. Returns, if it was possible to insert the value,
-    /// the index to which it was inserted. If it was not possible to do the insert, returns the
-    /// key that was going to be inserted. If this function returns successfully, it is guaranteed
-    /// that the key is located at the index specified, but its matching value is not set to
-    /// anything meaningful. This is the callers responsibility.
-    fn insert_one_sided<K: Hash, V, H: BuildHasher>(
-        key: K,
-        key_data: &mut [Bucket<K, usize, B>],
-        value_data: &mut [Bucket<V, usize, B>],
-        hasher: &H,
-    ) -> Result<usize, K> {
-        let len = key_data.len();
-        let ideal_index = Self::find_ideal_index(&key, hasher, len);
+    /// Calls a closure on every key-value pair in the hashmap, in an arbitrary order. This visits
+    /// the same pairs as `iter`, but keeps the iteration internal to the hashmap, which makes it
+    /// easy to wrap for callers that can't hold on to a Rust iterator, such as over FFI.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    /// map.insert("World", 7);
+    ///
+    /// let mut total = 0;
+    /// map.for_each_pair(|_, &right| total += right);
+    /// assert_eq!(12, total);
+    /// ```
+    pub fn for_each_pair<F: FnMut(&L, &R)>(&self, mut f: F) {
+        for (left, right) in self.iter() {
+            f(left, right);
+        }
+    }
 
-        if key_data[ideal_index].neighbourhood.full() {
-            return Err(key);
+    /// An iterator visiting all key-value pairs in the order in which they were inserted, from the
+    /// oldest to the newest. Returns None if the hashmap doesn't keep track of its insertion
+    /// order: see the `ordered` method of `BiMapBuilder`.
+    ///
+    /// ```
+    /// # use isomorphism::{BiMap, BiMapBuilder};
+    /// let mut map: BiMap<&str, u64> = BiMapBuilder::new().ordered().finish();
+    /// map.insert("Hello", 5);
+    /// map.insert("World", 7);
+    /// map.insert("Hello", 8);
+    ///
+    /// let pairs: Vec<_> = map.iter_ordered().unwrap().collect();
+    /// assert_eq!(vec![(&"World", &7), (&"Hello", &8)], pairs);
+    ///
+    /// let unordered: BiMap<&str, u64> = BiMap::new();
+    /// assert!(unordered.iter_ordered().is_none());
+    /// ```
+    pub fn iter_ordered(&self) -> Option<impl Iterator<Item = (&L, &R)>> {
+        if !self.order.is_enabled() {
+            return None;
         }
 
-        let nearest = key_data[ideal_index..]
-            .iter()
-            .chain(key_data[..ideal_index].iter())
-            .enumerate()
-            .find(|&(_, bucket)| bucket.data.is_none())
-            .map(|(offset, _)| offset);
-        if let Some(offset) = nearest {
-            // is this free space within the neighbourhood?
-            if offset < B::size() {
-                // insert and we're done
-                let index = (offset + ideal_index) % len;
-                Self::mark_as_full(ideal_index, index, key_data);
-                key_data[index].data = Some((key, usize::max_value(), ideal_index));
-                Ok(index)
-            } else {
-                // need to make room -> find a space, boot the old thing out to make room, insert,
-                // repeat
-                let max_offset = (ideal_index + B::size()) % len;
-                let nearest = (0..)
-                    .map(|i| (len + max_offset - i) % len)
-                    .take(B::size() - 1)
-                    .skip(1)
-                    .find(|&i| {
-                        let &(_, _, ideal) = key_data[i].data.as_ref().unwrap();
-                        // check if the bucket we're planning to displace is closer to the blank
-                        // space than we are, and make sure that it is close enough for us to move
-                        // into its spot (more complicated due to wrap around)
-                        if ideal > ideal_index {
-                            ideal - ideal_index < B::size()
-                        } else if ideal < ideal_index {
-                            ideal < max_offset && max_offset < ideal
-                        } else {
-                            false
-                        }
-                    });
-                if let Some(index) = nearest {
-                    // we've found a spot to insert into
-                    let (new_key, new_value, new_ideal) = key_data[index].data.take().unwrap();
-                    Self::mark_as_empty(new_ideal, index, key_data);
-                    key_data[index].data = Some((key, usize::max_value(), ideal_index));
-                    Self::mark_as_full(ideal_index, index, key_data);
-                    match Self::insert_one_sided(new_key, key_data, value_data, hasher) {
-                        Ok(new_key_index) => {
-                            // the replacement worked
-                            {
-                                let &mut (_, ref mut paired_key_index, _) =
-                                    value_data[new_value].data.as_mut().unwrap();
-                                *paired_key_index = new_key_index;
-                                let &mut (_, ref mut paired_value_index, _) =
-                                    key_data[new_key_index].data.as_mut().unwrap();
-                                *paired_value_index = new_value;
-                            }
-                            Self::mark_as_full(new_ideal, new_key_index, key_data);
+        let left_data = &self.left_data;
+        let right_data = &self.right_data;
+        Some(self.order.buckets().map(move |left_index| {
+            let &(ref left, right_index, _) = left_data[left_index].data.as_ref().unwrap();
+            let &(ref right, ..) = right_data[right_index].data.as_ref().unwrap();
+            (left, right)
+        }))
+    }
 
-                            // finish our insert and return
-                            Ok(index)
-                        }
-                        Err(new_key) => {
-                            // the replacement failed - undo our insert
-                            Self::mark_as_full(new_ideal, index, key_data);
-                            Self::mark_as_empty(ideal_index, index, key_data);
-                            let (key, _, _) = key_data[index].data.take().unwrap();
-                            key_data[index].data = Some((new_key, new_value, new_ideal));
-                            Err(key)
-                        }
-                    }
-                } else {
-                    // no spot can be inserted into
-                    Err(key)
-                }
-            }
+    /// Gets the pair whose right value is stored at a given index within the hashmap, if there is
+    /// one. See `iter_with_right_index` for where these indices come from.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    ///
+    /// let (_, _, index) = map.iter_with_right_index().next().unwrap();
+    /// assert_eq!(Some((&"Hello", &5)), map.pair_at_right_index(index));
+    /// assert_eq!(None, map.pair_at_right_index(usize::MAX));
+    /// ```
+    pub fn pair_at_right_index(&self, index: usize) -> Option<(&L, &R)> {
+        // both sides have the same number of buckets, so right indices split in the same way
+        let (left_data, right_data, index) = self.buckets_at(index);
+        let &(ref right, left_index, _) = right_data.get(index)?.data.as_ref()?;
+        let &(ref left, ..) = left_data[left_index].data.as_ref().unwrap();
+        Some((left, right))
+    }
+
+    /// Finds the buckets that an index into the hashmap refers to, and the index within them.
+    /// Indices count through the current buckets, and then on through the buckets of a shrink in
+    /// progress, so an index past the end of both is also past the end of the buckets returned.
+    fn buckets_at(&self, index: usize) -> (&Buckets<L, B>, &Buckets<R, B>, usize) {
+        if index < self.left_data.len() {
+            (&self.left_data, &self.right_data, index)
         } else {
-            // there is no free space
-            Err(key)
+            (&self.old_left_data, &self.old_right_data, index - self.left_data.len())
         }
     }
 
-    /// Inserts an (L, R) pair into the hashmap. Returned is a (R, L) tuple of options. The
-    /// `Option<R>` is the value that was previously associated with the inserted L (or lack
-    /// thereof), and vice versa for the `Option<L>`.
+    /// Returns whether a shrink started by `shrink_incremental` is still in progress.
+    fn is_shrinking(&self) -> bool {
+        !self.old_left_data.is_empty()
+    }
+
+    /// Returns clones of every pair in the hashmap, sorted by their left keys. Unlike `iter`, the
+    /// order doesn't depend on the layout of the hashmap, so hashmaps with the same contents
+    /// always give the same output. This is intended for producing canonical exports, such as for
+    /// hashing or signing.
     ///
     /// ```
     /// # use isomorphism::BiMap;
     /// let mut map = BiMap::new();
+    /// map.insert("World", 7);
+    /// map.insert("Hello", 5);
     ///
-    /// // neither "Hello" nor 5 were previously mapped to anything, so nothing is returned.
-    /// assert_eq!((None, None), map.insert("Hello", 5));
+    /// assert_eq!(vec![("Hello", 5), ("World", 7)], map.sorted_pairs());
+    /// ```
+    pub fn sorted_pairs(&self) -> Vec<(L, R)>
+    where
+        L: Ord + Clone,
+        R: Clone,
+    {
+        let mut pairs: Vec<_> = self.iter().collect();
+        pairs.sort_unstable_by_key(|&(left, _)| left);
+        pairs
+            .into_iter()
+            .map(|(left, right)| (left.clone(), right.clone()))
+            .collect()
+    }
+
+    /// Groups every pair in the hashmap by a key derived from its left key, such as the first
+    /// character of a string. The pairs within each group are in an arbitrary order.
     ///
-    /// // "Hello" was previously mapped to 5, so remapping it to 7 means that the 5 got evicted
-    /// // from the hashmap and is therefore returned. 7 was not previously mapped to anything,
-    /// // though.
-    /// assert_eq!((Some(5), None), map.insert("Hello", 7));
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 1);
+    /// map.insert("Help", 2);
+    /// map.insert("World", 3);
     ///
-    /// // Note now that inserting "Hello" with a new right value means that 5 no longer exists in
-    /// // the hashmap.
-    /// assert_eq!(None, map.get_right(&5));
+    /// let groups = map.group_left_by(|left| left.chars().next());
+    /// assert_eq!(2, groups[&Some('H')].len());
+    /// assert_eq!(vec![(&"World", &3)], groups[&Some('W')]);
     /// ```
-    pub fn insert(&mut self, left: L, right: R) -> (Option<R>, Option<L>) {
-        let output = {
-            let &mut BiMap {
-                ref mut len,
-                ref mut left_data,
-                ref mut right_data,
-                ref left_hasher,
-                ref right_hasher,
-            } = self;
-            match Self::remove(&left, left_data, right_data, left_hasher, right_hasher, len) {
-                Some((old_left, old_right)) => if old_right == right {
-                    (Some(old_right), Some(old_left))
-                } else {
-                    (
-                        Some(old_right),
-                        Self::remove(
-                            &right,
-                            right_data,
-                            left_data,
-                            right_hasher,
-                            left_hasher,
-                            len,
-                        ).map(|(_key, value)| value),
-                    )
-                },
-                None => (
-                    None,
-                    Self::remove(
-                        &right,
-                        right_data,
-                        left_data,
-                        right_hasher,
-                        left_hasher,
-                        len,
-                    ).map(|(_key, value)| value),
-                ),
-            }
+    pub fn group_left_by<K, F>(&self, mut key_fn: F) -> HashMap<K, Vec<(&L, &R)>>
+    where
+        K: Hash + Eq,
+        F: FnMut(&L) -> K,
+    {
+        let mut groups = HashMap::new();
+        for (left, right) in self.iter() {
+            groups.entry(key_fn(left)).or_insert_with(Vec::new).push((left, right));
+        }
+        groups
+    }
+
+    /// An iterator visiting all key-value pairs whose left key starts with a given prefix, in an
+    /// arbitrary order. As the hashmap keeps no ordering of its keys, this scans every pair.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 1);
+    /// map.insert("Help", 2);
+    /// map.insert("World", 3);
+    ///
+    /// let mut matches: Vec<_> = map.left_with_prefix("Hel").map(|(_, &right)| right).collect();
+    /// matches.sort();
+    /// assert_eq!(vec![1, 2], matches);
+    /// ```
+    pub fn left_with_prefix<'a>(&'a self, prefix: &'a str) -> impl Iterator<Item = (&'a L, &'a R)>
+    where
+        L: AsRef<str>,
+    {
+        self.iter().filter(move |&(left, _)| left.as_ref().starts_with(prefix))
+    }
+
+    /// Removes every pair from the hashmap, keeping the memory that was allocated for them. All of
+    /// the left keys are dropped before any of the right keys.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    ///
+    /// let capacity = map.capacity();
+    /// map.clear();
+    /// assert!(map.is_empty());
+    /// assert_eq!(capacity, map.capacity());
+    /// ```
+    pub fn clear(&mut self)
+    where
+        B: BitField,
+    {
+        let buckets = self.left_data.len();
+        self.clear_to(buckets);
+    }
+
+    /// Removes every pair from the hashmap in the same way as `clear`, but if the hashmap has grown
+    /// since it was created, also shrinks it back down to the capacity it was created with. This
+    /// frees the memory of a hashmap that is repeatedly filled up and cleared out, while keeping
+    /// enough to refill it to its usual size without resizing.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::with_capacity(100);
+    /// let capacity = map.capacity();
+    ///
+    /// map.extend((0..1000).map(|i| (i, i)));
+    /// assert!(map.capacity() > capacity);
+    ///
+    /// map.clear_to_baseline();
+    /// assert!(map.is_empty());
+    /// assert_eq!(capacity, map.capacity());
+    /// ```
+    pub fn clear_to_baseline(&mut self)
+    where
+        B: BitField,
+    {
+        let buckets = cmp::min(self.left_data.len(), self.baseline_buckets);
+        self.clear_to(buckets);
+    }
+
+    /// Removes every pair from the hashmap, leaving it with a given number of empty buckets. The
+    /// existing buckets are emptied in place if there are already that many of them, and replaced
+    /// otherwise.
+    fn clear_to(&mut self, buckets: usize)
+    where
+        B: BitField,
+    {
+        let old_buckets = self.left_data.len();
+        self.len = 0;
+        self.incomplete = 0;
+        self.layout_generation += 1;
+        self.modifications += 1;
+        self.order.reset(buckets);
+        // the right keys of a shrink in progress are only dropped after all of the left keys
+        self.old_left_data = Bucket::empty_vec(0);
+        self.old_len = 0;
+        self.old_next = 0;
+
+        if buckets == old_buckets {
+            // empty every left bucket before any right bucket, so the left keys are dropped first
+            for bucket in self.left_data.iter_mut() {
+                bucket.data = None;
+                bucket.neighbourhood = B::zero();
+            }
+            for bucket in self.right_data.iter_mut() {
+                bucket.data = None;
+                bucket.neighbourhood = B::zero();
+            }
+        } else {
+            self.left_data = Bucket::empty_vec(buckets);
+            self.right_data = Bucket::empty_vec(buckets);
+            self.resizes += 1;
+            if let Some(ref mut on_resize) = self.on_resize {
+                on_resize(old_buckets, buckets);
+            }
+        }
+        self.old_right_data = Bucket::empty_vec(0);
+    }
+
+    /// Removes every pair from the hashmap in the same way as `clear`, and also replaces both of
+    /// its hashers with new ones. With the default `RandomState` hashers, the new hashers have
+    /// fresh random seeds, so that someone who learned how the old seeds arranged the keys can't
+    /// use that to predict how keys will be arranged from now on. As the hashmap is empty, no keys
+    /// have to be moved for the new hashers.
+    ///
+    /// This is the case even with the `debug-deterministic` feature enabled, so the hashmap no
+    /// longer iterates in the same order as other hashmaps with the same pairs afterwards.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    ///
+    /// map.clear_reseed();
+    /// assert!(map.is_empty());
+    /// ```
+    pub fn clear_reseed(&mut self)
+    where
+        LH: Default,
+        RH: Default,
+        B: BitField,
+    {
+        self.clear();
+        self.left_hasher = Default::default();
+        self.right_hasher = Default::default();
+    }
+
+    /// Removes every pair from the hashmap, returning them in an iterator. The hashmap keeps the
+    /// same capacity. If the iterator is dropped before it has returned every pair, the remaining
+    /// left keys are dropped before any of the remaining right keys.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    ///
+    /// let pairs: Vec<_> = map.drain().collect();
+    /// assert_eq!(vec![("Hello", 5)], pairs);
+    /// assert!(map.is_empty());
+    /// ```
+    pub fn drain(&mut self) -> IntoIter<L, R, B>
+    where
+        B: BitField,
+    {
+        let capacity = self.left_data.len();
+        self.len = 0;
+        self.old_len = 0;
+        self.old_next = 0;
+        self.incomplete = 0;
+        self.layout_generation += 1;
+        self.modifications += 1;
+        self.order.reset(capacity);
+        let left_data = mem::replace(&mut self.left_data, Bucket::empty_vec(capacity));
+        let right_data = mem::replace(&mut self.right_data, Bucket::empty_vec(capacity));
+        let old_left_data = mem::replace(&mut self.old_left_data, Bucket::empty_vec(0));
+        let old_right_data = mem::replace(&mut self.old_right_data, Bucket::empty_vec(0));
+        IntoIter::shrinking(left_data, right_data, old_left_data, old_right_data)
+    }
+
+    /// Hashes a single pair with a fixed, unseeded hasher, so that the result is the same for
+    /// every hashmap regardless of its layout or configured hashers.
+    fn pair_hash(left: &L, right: &R) -> u64
+    where
+        L: Hash,
+        R: Hash,
+    {
+        let mut hasher = DigestHasher::new();
+        left.hash(&mut hasher);
+        right.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Returns a 64 bit digest of the pairs inside the hashmap. The digest depends only on which
+    /// pairs are in the hashmap - not on the order in which they were inserted, the capacity of
+    /// the hashmap, or its configured hashers - and is the same from one run of a program to the
+    /// next. This makes it suitable for use as a fingerprint of the contents of the hashmap, such
+    /// as for detecting whether the hashmap has changed between two snapshots.
+    ///
+    /// The pairs are hashed with 64 bit FNV-1a, with integers fed to it as little endian bytes and
+    /// `usize` widened to 64 bits, so the digest is also the same across platforms and releases,
+    /// as long as the `Hash` implementations of the keys don't change.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut first = BiMap::new();
+    /// first.insert("Hello", 5);
+    /// first.insert("World", 7);
+    ///
+    /// let mut second = BiMap::new();
+    /// second.insert("World", 7);
+    /// second.insert("Hello", 5);
+    /// assert_eq!(first.content_hash(), second.content_hash());
+    ///
+    /// second.insert("Hello", 6);
+    /// assert_ne!(first.content_hash(), second.content_hash());
+    /// ```
+    pub fn content_hash(&self) -> u64
+    where
+        L: Hash,
+        R: Hash,
+    {
+        let pairs = self.iter()
+            .map(|(left, right)| Self::pair_hash(left, right))
+            .fold(0u64, |total, hash| total.wrapping_add(hash));
+
+        let mut hasher = DigestHasher::new();
+        hasher.write_u64(self.len() as u64);
+        hasher.write_u64(pairs);
+        hasher.finish()
+    }
+
+    /// Splits the pairs of the hashmap into `n` regions by the hashes of their left keys, and
+    /// returns a digest of each region in the same way as `content_hash`. The left keys are hashed
+    /// with the same fixed hasher as the digests, so a pair is in the same region in every
+    /// hashmap, on every platform. Two copies of a hashmap can compare their region hashes to
+    /// find which regions differ, and only reconcile the pairs in those regions.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is 0.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut first: BiMap<u64, u64> = (0..100).map(|i| (i, i)).collect();
+    /// let second: BiMap<u64, u64> = (0..100).rev().map(|i| (i, i)).collect();
+    /// assert_eq!(first.region_hashes(8), second.region_hashes(8));
+    ///
+    /// first.insert(5, 500);
+    /// let differing = first
+    ///     .region_hashes(8)
+    ///     .iter()
+    ///     .zip(second.region_hashes(8))
+    ///     .filter(|&(&first, second)| first != second)
+    ///     .count();
+    /// assert_eq!(1, differing);
+    /// ```
+    pub fn region_hashes(&self, n: usize) -> Vec<u64>
+    where
+        L: Hash,
+        R: Hash,
+    {
+        assert!(n != 0, "cannot split a bimap into 0 regions");
+
+        let mut regions = vec![(0u64, 0u64); n];
+        for (left, right) in self.iter() {
+            let mut hasher = DigestHasher::new();
+            left.hash(&mut hasher);
+            // reduce the full 64 bit hash, so that the region doesn't depend on the platform
+            let region = (hasher.finish() % n as u64) as usize;
+            let &mut (ref mut len, ref mut pairs) = &mut regions[region];
+            *len += 1;
+            *pairs = pairs.wrapping_add(Self::pair_hash(left, right));
+        }
+
+        regions
+            .into_iter()
+            .map(|(len, pairs)| {
+                let mut hasher = DigestHasher::new();
+                hasher.write_u64(len);
+                hasher.write_u64(pairs);
+                hasher.finish()
+            })
+            .collect()
+    }
+
+    /// Splits the hashmap into a given number of owning iterators, which between them yield every
+    /// pair in the hashmap exactly once. Each iterator owns its own pairs, and the pairs are
+    /// spread as evenly as possible between the iterators, so they can be sent to separate
+    /// threads to be processed in parallel.
+    ///
+    /// Splitting the hashmap isn't itself done in parallel: every pair is moved out of the
+    /// hashmap and into its chunk on the calling thread, before this returns. Only the work done
+    /// on the chunks afterwards can be spread between threads.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunks` is 0.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// use std::thread;
+    ///
+    /// let map: BiMap<u64, u64> = (0..10).map(|i| (i, i * 2)).collect();
+    ///
+    /// let handles: Vec<_> = map.into_par_chunks(4)
+    ///     .into_iter()
+    ///     .map(|chunk| thread::spawn(move || chunk.map(|(_, right)| right).sum::<u64>()))
+    ///     .collect();
+    ///
+    /// let total: u64 = handles.into_iter().map(|handle| handle.join().unwrap()).sum();
+    /// assert_eq!(90, total);
+    /// ```
+    pub fn into_par_chunks(self, chunks: usize) -> Vec<IntoIter<L, R, B>>
+    where
+        B: BitField,
+    {
+        assert!(chunks != 0, "cannot split a bimap into 0 chunks");
+
+        let (per_chunk, remainder) = (self.len() / chunks, self.len() % chunks);
+        let mut pairs = self.into_iter();
+
+        (0..chunks)
+            .map(|chunk| {
+                let size = if chunk < remainder { per_chunk + 1 } else { per_chunk };
+                let mut left_data = Vec::with_capacity(size);
+                let mut right_data = Vec::with_capacity(size);
+
+                for (index, (left, right)) in pairs.by_ref().take(size).enumerate() {
+                    let mut left_bucket = Bucket::empty();
+                    left_bucket.data = Some((left, index, index));
+                    left_data.push(left_bucket);
+
+                    let mut right_bucket = Bucket::empty();
+                    right_bucket.data = Some((right, index, index));
+                    right_data.push(right_bucket);
+                }
+
+                IntoIter::new(left_data.into(), right_data.into())
+            })
+            .collect()
+    }
+}
+
+impl<L, R, LH, RH, B> BiMap<L, R, LH, RH, B>
+where
+    L: Hash + Eq,
+    R: Hash + Eq,
+    LH: BuildHasher,
+    RH: BuildHasher,
+    B: BitField,
+{
+    /// Finds the ideal position of a key within the hashmap.
+    fn find_ideal_index<K: Hash, H: BuildHasher>(key: &K, hasher: &H, len: usize) -> usize {
+        hasher.hash_one(key) as usize % len
+    }
+
+    /// Find the bitfield associated with an ideal hash index in a hashmap array, and mark a given
+    /// index as full.
+    fn mark_as_full<K>(ideal_index: usize, actual_index: usize, data: &mut [Bucket<K, usize, B>]) {
+        let offset = (data.len() + actual_index - ideal_index) % data.len();
+        data[ideal_index].neighbourhood = data[ideal_index].neighbourhood | B::one_at(offset);
+    }
+
+    /// Finds the bitflield associated with an ideal hash index in a hashmap array, and mark a
+    /// given index as empty.
+    fn mark_as_empty<K>(ideal_index: usize, actual_index: usize, data: &mut [Bucket<K, usize, B>]) {
+        let offset = (data.len() + actual_index - ideal_index) % data.len();
+        data[ideal_index].neighbourhood = data[ideal_index].neighbourhood & B::zero_at(offset);
+    }
+
+    /// Inserts a given key into its data bucket. As this may do reshuffling, it requires a
+    /// reference to the value data buckets also. Returns, if it was possible to insert the value,
+    /// the index to which it was inserted. If it was not possible to do the insert, returns the
+    /// key that was going to be inserted. If this function returns successfully, it is guaranteed
+    /// that the key is located at the index specified, but its matching value is left as
+    /// INCOMPLETE. Pairing it is the callers responsibility. The layout generation is
+    /// incremented if any other key had to be moved to make room, and the order is told about
+    /// every key that is moved.
+    fn insert_one_sided<K: Hash, V, H: BuildHasher>(
+        key: K,
+        key_data: &mut [Bucket<K, usize, B>],
+        value_data: &mut [Bucket<V, usize, B>],
+        hasher: &H,
+        layout_generation: &mut usize,
+        order: &mut Order,
+    ) -> Result<usize, K> {
+        let len = key_data.len();
+        let ideal_index = Self::find_ideal_index(&key, hasher, len);
+
+        if key_data[ideal_index].neighbourhood.full() {
+            return Err(key);
+        }
+
+        let nearest = key_data[ideal_index..]
+            .iter()
+            .chain(key_data[..ideal_index].iter())
+            .enumerate()
+            .find(|&(_, bucket)| bucket.data.is_none())
+            .map(|(offset, _)| offset);
+        if let Some(offset) = nearest {
+            // is this free space within the neighbourhood?
+            if offset < B::size() {
+                // insert and we're done
+                let index = (offset + ideal_index) % len;
+                Self::mark_as_full(ideal_index, index, key_data);
+                key_data[index].data = Some((key, INCOMPLETE, ideal_index));
+                key_data[index].generation = key_data[index].generation.wrapping_add(1);
+                Ok(index)
+            } else {
+                // need to make room -> find a space, boot the old thing out to make room, insert,
+                // repeat
+                let max_offset = (ideal_index + B::size()) % len;
+                let nearest = (0..)
+                    .map(|i| (len + max_offset - i) % len)
+                    .take(B::size())
+                    .skip(1)
+                    .find(|&i| {
+                        let &(_, _, ideal) = key_data[i].data.as_ref().unwrap();
+                        // check if the bucket we're planning to displace is closer to the blank
+                        // space than we are, and make sure that it is close enough for us to move
+                        // into its spot. Distances are measured forwards from our ideal index, so
+                        // that neighbourhoods that wrap around the end are handled too
+                        let distance = (len + ideal - ideal_index) % len;
+                        distance != 0 && distance < B::size()
+                    });
+                if let Some(index) = nearest {
+                    // we've found a spot to insert into
+                    let (new_key, new_value, new_ideal) = key_data[index].data.take().unwrap();
+                    order.lift(index);
+                    Self::mark_as_empty(new_ideal, index, key_data);
+                    key_data[index].data = Some((key, INCOMPLETE, ideal_index));
+                    key_data[index].generation = key_data[index].generation.wrapping_add(1);
+                    Self::mark_as_full(ideal_index, index, key_data);
+                    match Self::insert_one_sided(
+                        new_key,
+                        key_data,
+                        value_data,
+                        hasher,
+                        layout_generation,
+                        order,
+                    ) {
+                        Ok(new_key_index) => {
+                            // the replacement worked
+                            *layout_generation += 1;
+                            order.place(new_key_index);
+                            {
+                                // an incomplete left key has no value to point back at it
+                                if new_value != INCOMPLETE {
+                                    let &mut (_, ref mut paired_key_index, _) =
+                                        value_data[new_value].data.as_mut().unwrap();
+                                    *paired_key_index = new_key_index;
+                                }
+                                let &mut (_, ref mut paired_value_index, _) =
+                                    key_data[new_key_index].data.as_mut().unwrap();
+                                *paired_value_index = new_value;
+                            }
+                            Self::mark_as_full(new_ideal, new_key_index, key_data);
+
+                            // finish our insert and return
+                            Ok(index)
+                        }
+                        Err(new_key) => {
+                            // the replacement failed - undo our insert
+                            Self::mark_as_full(new_ideal, index, key_data);
+                            Self::mark_as_empty(ideal_index, index, key_data);
+                            let (key, _, _) = key_data[index].data.take().unwrap();
+                            key_data[index].data = Some((new_key, new_value, new_ideal));
+                            order.place(index);
+                            Err(key)
+                        }
+                    }
+                } else {
+                    // no spot can be inserted into
+                    Err(key)
+                }
+            }
+        } else {
+            // there is no free space
+            Err(key)
+        }
+    }
+
+    /// Inserts an (L, R) pair into the hashmap. Returned is a (R, L) tuple of options. The
+    /// `Option<R>` is the value that was previously associated with the inserted L (or lack
+    /// thereof), and vice versa for the `Option<L>`.
+    ///
+    /// # Panics
+    ///
+    /// A pair can't be placed when as many keys as fit in a neighbourhood (the number of bits in
+    /// the bitfield) already have exactly the same hash as the L or the R. Keys with the same hash
+    /// always share an ideal bucket, so growing the hashmap can't make room for them. If the
+    /// hashmap reseeds on collisions, which it does by default, its hashers are replaced first,
+    /// so that the keys get different hashes: see the `reseed_on_collisions` method of
+    /// `BiMapBuilder`. Panics if the pair still can't be placed after that, or straight away if the
+    /// hashmap doesn't reseed, rather than growing until it runs out of memory. This can only be
+    /// caused by a hasher that gives many keys the same hash whatever its seed. Use `try_insert`
+    /// to get the pair back instead.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    ///
+    /// // neither "Hello" nor 5 were previously mapped to anything, so nothing is returned.
+    /// assert_eq!((None, None), map.insert("Hello", 5));
+    ///
+    /// // "Hello" was previously mapped to 5, so remapping it to 7 means that the 5 got evicted
+    /// // from the hashmap and is therefore returned. 7 was not previously mapped to anything,
+    /// // though.
+    /// assert_eq!((Some(5), None), map.insert("Hello", 7));
+    ///
+    /// // Note now that inserting "Hello" with a new right value means that 5 no longer exists in
+    /// // the hashmap.
+    /// assert_eq!(None, map.get_right(&5));
+    /// ```
+    pub fn insert(&mut self, left: L, right: R) -> (Option<R>, Option<L>) {
+        let (old_right, old_left, _evicted) = self.insert_evicting(left, right);
+        (old_right, old_left)
+    }
+
+    /// Inserts an (L, R) pair into the hashmap, in the same way as `insert`, and returns references
+    /// to the pair as it is now stored in the hashmap.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// let (left, right) = map.insert_ref(String::from("Hello"), String::from("World"));
+    /// assert_eq!("Hello World", format!("{} {}", left, right));
+    /// ```
+    pub fn insert_ref(&mut self, left: L, right: R) -> (&L, &R) {
+        let index = self.insert_pair(left, right, false).index;
+        let &(ref left, right_index, _) = self.left_data[index].data.as_ref().unwrap();
+        let &(ref right, ..) = self.right_data[right_index].data.as_ref().unwrap();
+        (left, right)
+    }
+
+    /// Inserts an (L, R) pair into the hashmap, in the same way as `insert`, and also returns the
+    /// pair that was evicted to make room for it if the hashmap was created by
+    /// `with_lru_capacity` and was already full. Hashmaps without an LRU capacity never evict
+    /// pairs.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::with_lru_capacity(2);
+    /// assert_eq!((None, None, None), map.insert_evicting("Hello", 5));
+    /// assert_eq!((None, None, None), map.insert_evicting("World", 7));
+    ///
+    /// // the map is full, so the pair that was inserted first is evicted
+    /// assert_eq!((None, None, Some(("Hello", 5))), map.insert_evicting("Hashmaps", 9));
+    ///
+    /// // replacing a pair makes room for itself, so nothing else is evicted
+    /// assert_eq!((Some(7), None, None), map.insert_evicting("World", 8));
+    /// ```
+    pub fn insert_evicting(&mut self, left: L, right: R) -> (Option<R>, Option<L>, Option<(L, R)>) {
+        let inserted = self.insert_pair(left, right, false);
+        (inserted.old_right, inserted.old_left, inserted.evicted)
+    }
+
+    /// Creates a hashmap from pairs whose left keys are sorted in strictly increasing order, and
+    /// so are all unique. As no left key can already be in the hashmap, inserting each pair skips
+    /// looking for one, and the hashmap is grown once for the number of pairs given rather than
+    /// as they are inserted. Right keys are not assumed to be unique, and a pair whose
+    /// right key is already in the hashmap replaces the old pair as it would with `insert`.
+    ///
+    /// If the left keys turn out not to be sorted, every pair is inserted with `insert` instead,
+    /// so the result is still the same as collecting the pairs with `FromIterator`.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let map: BiMap<_, _> = BiMap::from_sorted_unique(vec![(1, 'a'), (2, 'b'), (3, 'c')]);
+    /// assert_eq!(3, map.len());
+    /// assert_eq!(Some(&'b'), map.get_left(&2));
+    /// assert_eq!(Some(&3), map.get_right(&'c'));
+    /// ```
+    pub fn from_sorted_unique<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = (L, R)>,
+        L: Ord,
+        LH: Default,
+        RH: Default,
+    {
+        let pairs: Vec<(L, R)> = iter.into_iter().collect();
+        let mut output = BiMapBuilder::new()
+            .left_hasher(Default::default())
+            .right_hasher(Default::default())
+            .reseed_on_collisions()
+            .bitfield::<B>()
+            .finish();
+        output.grow_for(pairs.len());
+
+        // a left key can only be repeated after a smaller one if the keys aren't sorted
+        let sorted = pairs.windows(2).all(|pairs| pairs[0].0 < pairs[1].0);
+        for (left, right) in pairs {
+            output.insert_pair(left, right, sorted);
+        }
+        output
+    }
+
+    /// Inserts an (L, R) pair into the hashmap, as described by `insert_evicting`. If `left_is_new`
+    /// is set, the caller has guaranteed that the left key isn't already in the hashmap, so no
+    /// existing pair is looked for under it. Also returns the index that the left key ended up
+    /// at.
+    ///
+    /// Panics if the pair can't be placed, as described by `try_insert_pair`.
+    fn insert_pair(&mut self, left: L, right: R, left_is_new: bool) -> Inserted<L, R> {
+        match self.try_insert_pair(left, right, left_is_new) {
+            Ok(inserted) => inserted,
+            Err(_) => panic!(
+                "cannot place a pair in the bimap: at least {} keys share the hash of one of its \
+                 keys",
+                B::size()
+            ),
+        }
+    }
+
+    /// Inserts an (L, R) pair into the hashmap in the same way as `insert_pair`, but gives the pair
+    /// back if it can never be placed. This happens when as many keys as fit in a neighbourhood
+    /// already have exactly the same hash as one of its keys, as every one of them has the same
+    /// ideal bucket whatever the number of buckets, so growing the hashmap can't make room. Any
+    /// pairs that shared a key with the pair have still been removed by then.
+    fn try_insert_pair(
+        &mut self,
+        left: L,
+        right: R,
+        left_is_new: bool,
+    ) -> Result<Inserted<L, R>, (L, R)> {
+        self.modifications += 1;
+
+        let (old_right, old_left) = {
+            let removed = if left_is_new {
+                // an incomplete left key has a vacant entry, so it may still be in the hashmap
+                if self.incomplete != 0 {
+                    self.forget_incomplete(&left);
+                }
+                None
+            } else {
+                self.remove_by_left(&left)
+            };
+            match removed {
+                Some((old_left, old_right)) => if old_right == right {
+                    (Some(old_right), Some(old_left))
+                } else {
+                    (
+                        Some(old_right),
+                        self.remove_by_right(&right).map(|(_key, value)| value),
+                    )
+                },
+                None => (None, self.remove_by_right(&right).map(|(_key, value)| value)),
+            }
+        };
+
+        // make room for the new pair by evicting the oldest one, if the hashmap is full
+        let evicted = match self.lru_capacity {
+            Some(lru_capacity) if self.len() >= lru_capacity => {
+                self.order.first().map(|left_index| self.remove_at(left_index))
+            }
+            _ => None,
+        };
+
+        // attempt to insert, hold onto the keys if it fails
+        let overloaded = MAX_LOAD_FACTOR * self.len as f32 >= self.left_data.len() as f32;
+        let inserted: Result<usize, (L, R)> = if overloaded {
+            Err((left, right))
+        } else {
+            let &mut BiMap {
+                ref mut left_data,
+                ref mut right_data,
+                ref left_hasher,
+                ref right_hasher,
+                ref mut layout_generation,
+                ref mut order,
+                ..
+            } = self;
+            let inserted = Self::insert_one_sided(
+                left,
+                left_data,
+                right_data,
+                left_hasher,
+                layout_generation,
+                order,
+            );
+            match inserted {
+                Ok(left_index) => {
+                    // the order only follows the left keys, so the right keys can move freely
+                    let inserted = Self::insert_one_sided(
+                        right,
+                        right_data,
+                        left_data,
+                        right_hasher,
+                        layout_generation,
+                        &mut Order::disabled(),
+                    );
+                    match inserted {
+                        Ok(right_index) => {
+                            let &mut (_, ref mut paired_right_index, _) =
+                                left_data[left_index].data.as_mut().unwrap();
+                            *paired_right_index = right_index;
+
+                            let &mut (_, ref mut paired_left_index, _) =
+                                right_data[right_index].data.as_mut().unwrap();
+                            *paired_left_index = left_index;
+                            order.push_back(left_index);
+                            Ok(left_index)
+                        }
+                        Err(right) => {
+                            let (left, _, left_ideal) = left_data[left_index].data.take().unwrap();
+                            Self::mark_as_empty(left_ideal, left_index, left_data);
+                            Err((left, right))
+                        }
+                    }
+                }
+                Err(left) => Err((left, right)),
+            }
+        };
+
+        if inserted.is_ok() {
+            self.len += 1;
+        }
+
+        // neither key is in the hashmap any more, so the retries can skip looking for the left one
+        let index = match inserted {
+            Ok(index) => index,
+            Err((left, right)) => {
+                let saturated = !overloaded
+                    && (Self::saturated(&left, &self.left_data, &self.left_hasher)
+                        || Self::saturated(&right, &self.right_data, &self.right_hasher));
+                let collisions_persist = !overloaded
+                    && !self.reseeding
+                    && (saturated || self.collision_growths >= RESEED_AFTER_GROWTHS);
+                let reseed = if collisions_persist { self.reseed } else { None };
+                let redistribute = !overloaded
+                    && !self.redistributing
+                    && self.resize_policy == ResizePolicy::RedistributeFirst;
+                if let Some(reseed) = reseed {
+                    // growing hasn't made room for keys that keep colliding, or never could, so
+                    // their hashes are the problem rather than the number of buckets. Keys that
+                    // collide under one seed are unlikely to collide under another, so every pair
+                    // is moved into the same number of buckets with new hashers. If that doesn't
+                    // make room, the hashmap grows or gives up as usual, as the flag stays set.
+                    // The pairs of a shrink in progress were placed by the old hashers, so they
+                    // are all moved first.
+                    self.finish_shrink();
+                    self.collision_growths = 0;
+                    self.reseeding = true;
+                    let (left_hasher, right_hasher) = reseed();
+                    self.left_hasher = left_hasher;
+                    self.right_hasher = right_hasher;
+                    let capacity = self.left_data.len();
+                    self.reinsert(
+                        Bucket::empty_vec(capacity),
+                        Bucket::empty_vec(capacity),
+                        |_, _| true,
+                    );
+                    let inserted = self.try_insert_pair(left, right, true);
+                    self.reseeding = false;
+                    inserted?.index
+                } else if saturated {
+                    // no number of buckets can fit another key with this hash into the
+                    // neighbourhood, so growing would only go on until memory ran out
+                    return Err((left, right));
+                } else if redistribute {
+                    // a neighbourhood is full although the hashmap isn't, so the buckets there are
+                    // crowded rather than all taken. Every pair is moved again within the same
+                    // number of buckets, with new hashers if the hashmap reseeds, which breaks up
+                    // clusters of keys that only collide under the old seeds. This isn't a
+                    // resize, and is only tried once for each time the hashmap would have grown:
+                    // if it doesn't make room, the hashmap grows as usual, as the flag stays set.
+                    self.finish_shrink();
+                    self.redistributing = true;
+                    if let Some(reseed) = self.reseed {
+                        let (left_hasher, right_hasher) = reseed();
+                        self.left_hasher = left_hasher;
+                        self.right_hasher = right_hasher;
+                    }
+                    let capacity = self.left_data.len();
+                    self.reinsert(
+                        Bucket::empty_vec(capacity),
+                        Bucket::empty_vec(capacity),
+                        |_, _| true,
+                    );
+                    let inserted = self.try_insert_pair(left, right, true);
+                    self.redistributing = false;
+                    inserted?.index
+                } else {
+                    // resize, as we were unable to insert
+                    if overloaded {
+                        self.collision_growths = 0;
+                    } else {
+                        self.collision_growths += 1;
+                    }
+                    let capacity = self.left_data.len() * RESIZE_GROWTH_FACTOR;
+                    self.rehash(Bucket::empty_vec(capacity), Bucket::empty_vec(capacity));
+                    self.try_insert_pair(left, right, true)?.index
+                }
+            }
         };
 
+        Ok(Inserted {
+            index,
+            old_right,
+            old_left,
+            evicted,
+        })
+    }
+
+    /// Returns whether the neighbourhood of a key's ideal bucket is entirely taken up by keys with
+    /// exactly the same hash as it, in which case the key can never be placed.
+    fn saturated<K: Hash, KH: BuildHasher>(
+        key: &K,
+        key_data: &[Bucket<K, usize, B>],
+        key_hasher: &KH,
+    ) -> bool {
+        let len = key_data.len();
+        let hash = key_hasher.hash_one(key);
+        let ideal = hash as usize % len;
+        let same_hash = key_data[ideal]
+            .neighbourhood
+            .iter()
+            .filter_map(|offset| key_data[(ideal + offset) % len].data.as_ref())
+            .filter(|&&(ref candidate, ..)| key_hasher.hash_one(candidate) == hash)
+            .count();
+        same_hash >= B::size()
+    }
+
+    /// Replaces the buckets of the hashmap with a new (empty) set of buckets, and moves every pair
+    /// from the old buckets into the new ones.
+    fn rehash(
+        &mut self,
+        left_data: Box<[Bucket<L, usize, B>]>,
+        right_data: Box<[Bucket<R, usize, B>]>,
+    ) {
+        let (old_buckets, new_buckets) = (self.left_data.len(), left_data.len());
+        self.resizes += 1;
+        self.reinsert(left_data, right_data, |_, _| true);
+        if let Some(ref mut on_resize) = self.on_resize {
+            on_resize(old_buckets, new_buckets);
+        }
+    }
+
+    /// Replaces the buckets of the hashmap with a new (empty) set of buckets, and moves every pair
+    /// for which `f(&left, &mut right)` returns true from the old buckets into the new ones. If
+    /// insertion order is being tracked, the pairs are moved in that order, so that it is kept.
+    fn reinsert<F: FnMut(&L, &mut R) -> bool>(
+        &mut self,
+        left_data: Box<[Bucket<L, usize, B>]>,
+        right_data: Box<[Bucket<R, usize, B>]>,
+        mut f: F,
+    ) {
+        self.len = 0;
+        self.layout_generation += 1;
+        self.modifications += 1;
+        let mut old_left_data = mem::replace(&mut self.left_data, left_data);
+        let mut old_right_data = mem::replace(&mut self.right_data, right_data);
+        let old_order = self.order.reset(self.left_data.len());
+
+        // incomplete left keys aren't in the order, or paired with anything, so they are taken
+        // out first and moved after the pairs
+        let incomplete: Vec<L> = old_left_data
+            .iter_mut()
+            .filter(|bucket| bucket.data.is_some() && bucket.pair().is_none())
+            .map(|bucket| bucket.data.take().unwrap().0)
+            .collect();
+        self.incomplete -= incomplete.len();
+
+        if old_order.is_enabled() {
+            let pairs = old_order.take_pairs(&mut old_left_data, &mut old_right_data);
+            for (left, mut right) in pairs {
+                if f(&left, &mut right) {
+                    self.insert(left, right);
+                }
+            }
+        } else {
+            for (left, mut right) in IntoIter::new(old_left_data, old_right_data) {
+                if f(&left, &mut right) {
+                    self.insert(left, right);
+                }
+            }
+        }
+
+        for left in incomplete {
+            self.insert_incomplete(left);
+        }
+    }
+
+    /// Inserts a left key into the hashmap as an incomplete left key, which isn't paired with
+    /// anything, growing the hashmap as `insert` would to make room for it. The key must not
+    /// already be in the hashmap.
+    ///
+    /// Panics if the key can't be placed, as `insert` would.
+    fn insert_incomplete(&mut self, mut left: L) {
+        self.modifications += 1;
+        loop {
+            let overloaded = MAX_LOAD_FACTOR * self.len as f32 >= self.left_data.len() as f32;
+            if !overloaded {
+                let &mut BiMap {
+                    ref mut left_data,
+                    ref mut right_data,
+                    ref left_hasher,
+                    ref mut layout_generation,
+                    ref mut order,
+                    ..
+                } = self;
+                match Self::insert_one_sided(
+                    left,
+                    left_data,
+                    right_data,
+                    left_hasher,
+                    layout_generation,
+                    order,
+                ) {
+                    Ok(_) => {
+                        self.len += 1;
+                        self.incomplete += 1;
+                        return;
+                    }
+                    Err(unplaced) => left = unplaced,
+                }
+
+                if Self::saturated(&left, &self.left_data, &self.left_hasher) {
+                    panic!(
+                        "cannot place a left key in the bimap: at least {} keys share its hash",
+                        B::size()
+                    );
+                }
+            }
+
+            let capacity = self.left_data.len() * RESIZE_GROWTH_FACTOR;
+            self.rehash(Bucket::empty_vec(capacity), Bucket::empty_vec(capacity));
+        }
+    }
+
+    /// Resizes the hashmap once, up front, to the size that inserting `additional` more pairs one
+    /// at a time would grow it to, so that none of the resizes in between have to happen. Does
+    /// nothing if that size can't be allocated, leaving the inserts to resize the hashmap as they
+    /// need to.
+    fn grow_for(&mut self, additional: usize) {
+        // incomplete left keys take up buckets as well as the pairs
+        let pairs = (self.len + self.old_len).saturating_add(additional) as f32;
+        let mut buckets = self.left_data.len();
+        while MAX_LOAD_FACTOR * pairs >= buckets as f32 {
+            buckets = match buckets.checked_mul(RESIZE_GROWTH_FACTOR) {
+                Some(buckets) => buckets,
+                None => return,
+            };
+        }
+
+        if buckets > self.left_data.len() {
+            if let (Ok(left_data), Ok(right_data)) =
+                (Bucket::try_empty_vec(buckets), Bucket::try_empty_vec(buckets))
+            {
+                self.rehash(left_data, right_data);
+            }
+        }
+    }
+
+    /// Tries to reserve capacity for exactly `additional` more pairs to be inserted into the
+    /// hashmap, without allocating any extra space for future growth. Does nothing if the
+    /// capacity is already sufficient. Unlike a regular resize, this returns an error instead of
+    /// panicking if the new capacity overflows, or if the allocator reports a failure.
+    ///
+    /// As with the capacity given to the `BiMapBuilder`, it is likely but not guaranteed that
+    /// `additional` pairs can then be inserted without the hashmap being resized, as a large
+    /// number of hash collisions can still force a resize.
+    ///
+    /// ```
+    /// # use isomorphism::{BiMap, TryReserveError};
+    /// let mut map: BiMap<u64, char> = BiMap::new();
+    /// assert_eq!(Ok(()), map.try_reserve_exact(1000));
+    /// assert!(map.capacity() >= 999);
+    ///
+    /// assert_eq!(Err(TryReserveError::CapacityOverflow), map.try_reserve_exact(usize::MAX));
+    /// ```
+    pub fn try_reserve_exact(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        let buckets = self.buckets_for(additional)?;
+        self.try_grow_to(buckets)
+    }
+
+    /// Reserves capacity for at least `additional` more pairs to be inserted into the hashmap,
+    /// with room left over for keys that cluster together. Unlike `try_reserve_exact`, the hashmap
+    /// is made larger than the load factor alone requires, by a quarter of the buckets that it
+    /// requires plus one neighbourhood's worth. Does nothing if the capacity is already
+    /// sufficient.
+    ///
+    /// With the default 32 bit bitfield or a wider one, inserting `additional` pairs whose keys
+    /// are spread out by their hashers is then very unlikely to resize the hashmap. Narrower
+    /// bitfields have neighbourhoods that fill up long before the hashmap does, so they can still
+    /// be resized.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity overflows, or if the allocator reports a failure.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map: BiMap<u64, u64> = BiMap::new();
+    /// map.reserve(1000);
+    /// assert!(map.capacity() >= 1000);
+    ///
+    /// let capacity = map.capacity();
+    /// for i in 0..1000 {
+    ///     map.insert(i, i);
+    /// }
+    /// assert_eq!(capacity, map.capacity());
+    /// ```
+    pub fn reserve(&mut self, additional: usize) {
+        let result = self.buckets_for(additional).and_then(|buckets| {
+            let margin = buckets / RESERVE_MARGIN_DIVISOR + B::size();
+            let buckets = buckets.checked_add(margin).ok_or(TryReserveError::CapacityOverflow)?;
+            self.try_grow_to(buckets)
+        });
+        if let Err(error) = result {
+            panic!("{}", error);
+        }
+    }
+
+    /// Finds the number of buckets needed to hold `additional` more pairs than the hashmap
+    /// currently holds, without going over the maximum load factor.
+    fn buckets_for(&self, additional: usize) -> Result<usize, TryReserveError> {
+        let pairs = (self.len + self.old_len)
+            .checked_add(additional)
+            .ok_or(TryReserveError::CapacityOverflow)?;
+        let buckets = (pairs as f32 * MAX_LOAD_FACTOR).ceil();
+        if buckets >= usize::MAX as f32 {
+            Err(TryReserveError::CapacityOverflow)
+        } else {
+            Ok(buckets as usize)
+        }
+    }
+
+    /// Moves every pair into a given number of buckets, if that is more than the hashmap has.
+    fn try_grow_to(&mut self, buckets: usize) -> Result<(), TryReserveError> {
+        if buckets > self.left_data.len() {
+            let left_data = Bucket::try_empty_vec(buckets)?;
+            let right_data = Bucket::try_empty_vec(buckets)?;
+            self.rehash(left_data, right_data);
+        }
+
+        Ok(())
+    }
+
+    /// Shrinks the capacity of the hashmap as much as possible, while still holding all of its
+    /// pairs. Returns approximately how many bytes of heap memory were freed, as reported by
+    /// `memory_usage`. This may be 0, if the hashmap couldn't be shrunk.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map: BiMap<u64, char> = BiMap::with_capacity(1000);
+    /// map.insert(1, 'a');
+    ///
+    /// let before = map.memory_usage();
+    /// let freed = map.shrink_to_fit();
+    /// assert!(freed > 0);
+    /// assert_eq!(before - freed, map.memory_usage());
+    /// assert_eq!(Some(&'a'), map.get_left(&1));
+    /// ```
+    pub fn shrink_to_fit(&mut self) -> usize {
+        self.shrink_to(0)
+    }
+
+    /// Shrinks the capacity of the hashmap, so that it can hold at least `min_capacity` pairs, or
+    /// all of its pairs if there are more of them. Does nothing if the capacity is already lower
+    /// than this. Returns approximately how many bytes of heap memory were freed, as reported by
+    /// `memory_usage`.
+    ///
+    /// As with any other capacity, it is likely but not guaranteed that the pairs fit in the
+    /// shrunk hashmap: a large number of hash collisions can make it grow again.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map: BiMap<u64, char> = BiMap::with_capacity(1000);
+    /// map.insert(1, 'a');
+    ///
+    /// assert!(map.shrink_to(100) > 0);
+    /// assert!(map.capacity() >= 100 && map.capacity() < 1000);
+    /// assert_eq!(0, map.shrink_to(500));
+    /// ```
+    pub fn shrink_to(&mut self, min_capacity: usize) -> usize {
+        self.finish_shrink();
+        let before = self.memory_usage();
+
+        let pairs = cmp::max(cmp::max(self.len, min_capacity), 1);
+        let buckets = (pairs as f32 * MAX_LOAD_FACTOR).ceil() as usize;
+        if buckets < self.left_data.len() {
+            self.rehash(Bucket::empty_vec(buckets), Bucket::empty_vec(buckets));
+        }
+
+        before.saturating_sub(self.memory_usage())
+    }
+
+    /// Shrinks the capacity of the hashmap in the same way as `shrink_to_fit`, but spreads the
+    /// work over several calls, so that no one call has to move every pair. The first call
+    /// allocates the smaller buckets, and every call then moves at most `budget` pairs into them
+    /// from the old buckets, which are freed once they are empty. Returns true once the shrink has
+    /// finished, or straight away if the hashmap can't be shrunk.
+    ///
+    /// In between calls, the pairs are split between the two sets of buckets, and lookups,
+    /// iteration, inserts and removals by key or by handle look in both. Any other method that
+    /// modifies the hashmap finishes the shrink first, by moving every pair that is left. Each call
+    /// moves pairs around, so export cursors and handles from before it are rejected afterwards.
+    /// Hashmaps that keep track of their insertion order are shrunk all at once, by the first call.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map: BiMap<u64, u64> = BiMap::with_capacity(1000);
+    /// map.extend((0..100).map(|i| (i, i)));
+    ///
+    /// let before = map.memory_usage();
+    /// while !map.shrink_incremental(10) {
+    ///     assert_eq!(Some(&50), map.get_left(&50));
+    /// }
+    /// assert!(map.memory_usage() < before);
+    /// assert_eq!(100, map.len());
+    /// ```
+    pub fn shrink_incremental(&mut self, budget: usize) -> bool {
+        if !self.is_shrinking() {
+            let buckets = (cmp::max(self.len, 1) as f32 * MAX_LOAD_FACTOR).ceil() as usize;
+            if buckets >= self.left_data.len() {
+                return true;
+            } else if self.order.is_enabled() {
+                self.shrink_to_fit();
+                return true;
+            }
+
+            self.old_left_data = mem::replace(&mut self.left_data, Bucket::empty_vec(buckets));
+            self.old_right_data = mem::replace(&mut self.right_data, Bucket::empty_vec(buckets));
+            self.old_len = mem::replace(&mut self.len, 0);
+            self.old_next = 0;
+        }
+
+        self.layout_generation += 1;
+        self.modifications += 1;
+        let mut moved = 0;
+        while moved < budget && self.old_len > 0 {
+            let index = self.old_next;
+            self.old_next += 1;
+            if self.old_left_data[index].pair().is_some() {
+                let (left, right) =
+                    Self::take_pair(index, &mut self.old_left_data, &mut self.old_right_data);
+                self.old_len -= 1;
+                // neither key can be in the smaller buckets yet
+                self.insert_pair(left, right, true);
+                moved += 1;
+            } else if self.old_left_data[index].data.is_some() {
+                let left = self.take_incomplete(self.left_data.len() + index);
+                self.insert_incomplete(left);
+                moved += 1;
+            }
+        }
+
+        if self.old_len > 0 {
+            return false;
+        }
+
+        let (old_buckets, new_buckets) = (self.old_left_data.len(), self.left_data.len());
+        self.old_left_data = Bucket::empty_vec(0);
+        self.old_right_data = Bucket::empty_vec(0);
+        self.old_next = 0;
+        self.resizes += 1;
+        if let Some(ref mut on_resize) = self.on_resize {
+            on_resize(old_buckets, new_buckets);
+        }
+        true
+    }
+
+    /// Finishes a shrink started by `shrink_incremental`, if one is in progress, by moving every
+    /// pair that is left in the old buckets.
+    fn finish_shrink(&mut self) {
+        if self.is_shrinking() {
+            self.shrink_incremental(usize::MAX);
+        }
+    }
+
+    /// Inserts an (L, R) pair into the hashmap, returning the pair that was displaced to make
+    /// room for it, if any. This is a simpler alternative to `insert` for the common case where at
+    /// most one existing pair is disturbed by an insert.
+    ///
+    /// If the inserted L was previously paired with a right value, that pair is returned. If not,
+    /// but the inserted R was previously paired with a left value, that pair is returned instead.
+    /// If both the L and the R were previously in different pairs, both of those pairs are
+    /// removed, but only the pair that held the inserted L is returned.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// assert_eq!(None, map.replace("Hello", 5));
+    /// assert_eq!(Some(("Hello", 5)), map.replace("Hello", 7));
+    /// assert_eq!(Some(("Hello", 7)), map.replace("World", 7));
+    ///
+    /// map.insert("Hashmaps", 9);
+    /// // both the ("World", 7) and the ("Hashmaps", 9) pairs are removed here.
+    /// assert_eq!(Some(("World", 7)), map.replace("World", 9));
+    /// assert_eq!(1, map.len());
+    /// ```
+    pub fn replace(&mut self, left: L, right: R) -> Option<(L, R)> {
+        let (left_pair, right_pair) = (self.remove_by_left(&left), self.remove_by_right(&right));
+
+        self.insert(left, right);
+        left_pair.or_else(|| right_pair.map(|(right, left)| (left, right)))
+    }
+
+    /// Inserts an (L, R) pair into the hashmap, unless the pair is already present. Returns false
+    /// without modifying the hashmap if `left` is already mapped to `right` (and therefore `right`
+    /// to `left`), otherwise performs the insert and returns true. This is useful for update loops
+    /// that frequently resubmit pairs which have not changed.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// assert!(map.insert_if_changed("Hello", 5));
+    ///
+    /// // resubmitting the same pair does nothing
+    /// assert!(!map.insert_if_changed("Hello", 5));
+    ///
+    /// // but changing either side is an insert like any other
+    /// assert!(map.insert_if_changed("Hello", 7));
+    /// assert_eq!(Some(&7), map.get_left("Hello"));
+    /// assert_eq!(None, map.get_right(&5));
+    /// ```
+    pub fn insert_if_changed(&mut self, left: L, right: R) -> bool {
+        if self.get_left(&left) == Some(&right) {
+            false
+        } else {
+            self.insert(left, right);
+            true
+        }
+    }
+
+    /// Inserts an (L, R) pair into the hashmap, unless either key is already in it. Unlike
+    /// `insert`, this never removes an existing pair: if the L is already in the hashmap (paired
+    /// or incomplete), or the R is already paired with any left key, the hashmap is left
+    /// unmodified and the pair is given back as an error.
+    ///
+    /// The pair is also given back if it can never be placed, where `insert` would panic: see
+    /// `insert`. The hashmap may have grown by then, but holds the same pairs as before, unless it
+    /// is an LRU cache, in which case the oldest pair may have been evicted.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// assert_eq!(Ok(()), map.try_insert("Hello", 5));
+    /// assert_eq!(Err(("Hello", 7)), map.try_insert("Hello", 7));
+    /// assert_eq!(Err(("World", 5)), map.try_insert("World", 5));
+    /// assert_eq!(Some(&5), map.get_left("Hello"));
+    /// ```
+    pub fn try_insert(&mut self, left: L, right: R) -> Result<(), (L, R)> {
+        if self.contains_left(&left) || self.get_right(&right).is_some() {
+            Err((left, right))
+        } else {
+            self.try_insert_pair(left, right, true).map(|_| ())
+        }
+    }
+
+    /// Inserts an (L, R) pair into the hashmap in the same way as `insert`, but only if `check`
+    /// returns true when given the hashmap as it is and the pair. Otherwise, the hashmap is left
+    /// unmodified and the pair is given back as an error. This keeps the validation of inserts in
+    /// one place.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// let small = |_: &BiMap<&str, u64>, _: &&str, &right: &u64| right < 10;
+    ///
+    /// assert_eq!(Ok(()), map.insert_validated_by("Hello", 5, small));
+    /// assert_eq!(Err(("World", 50)), map.insert_validated_by("World", 50, small));
+    /// assert_eq!(1, map.len());
+    /// ```
+    pub fn insert_validated_by<F>(&mut self, left: L, right: R, check: F) -> Result<(), (L, R)>
+    where
+        F: FnOnce(&Self, &L, &R) -> bool,
+    {
+        if check(self, &left, &right) {
+            self.insert(left, right);
+            Ok(())
+        } else {
+            Err((left, right))
+        }
+    }
+
+    /// Looks up a key in the key_data section of the hashap, and if it exists returns it from the
+    /// value_data section of the hashap. Returns the value that is associated with the key, if it
+    /// exists.
+    fn get<'a, Q: ?Sized, K, V, KH>(
+        key: &Q,
+        key_data: &[Bucket<K, usize, B>],
+        value_data: &'a [Bucket<V, usize, B>],
+        key_hasher: &KH,
+    ) -> Option<&'a V>
+    where
+        Q: Hash + Eq,
+        K: Hash + Eq + Borrow<Q>,
+        KH: BuildHasher,
+    {
+        Self::get_by_hash(key_hasher.hash_one(key), key_data, value_data, |candidate_key| {
+            candidate_key.borrow() == key
+        })
+    }
+
+    /// Gets a key from the key_data section of the hashmap by its hash, and the value from the
+    /// value_data section that it is paired with, using `eq` to pick out the key from the others
+    /// in its neighbourhood.
+    fn get_by_hash<'a, K, V, F>(
+        hash: u64,
+        key_data: &[Bucket<K, usize, B>],
+        value_data: &'a [Bucket<V, usize, B>],
+        mut eq: F,
+    ) -> Option<&'a V>
+    where
+        F: FnMut(&K) -> bool,
+    {
+        let len = key_data.len();
+        if len == 0 {
+            // these are the buckets of a shrink that isn't in progress
+            return None;
+        }
+        let ideal = hash as usize % len;
+
+        let neighbourhood = key_data[ideal].neighbourhood;
+        if neighbourhood.empty() {
+            // nothing has this bucket as its ideal index
+            return None;
+        }
+
+        neighbourhood
+            .iter()
+            .filter_map(|offset| key_data[(ideal + offset) % len].data.as_ref())
+            .filter(|&&(ref candidate_key, ..)| eq(candidate_key))
+            .filter_map(|&(_, pair_index, _)| {
+                // an incomplete left key has no value to return
+                if pair_index == INCOMPLETE {
+                    return None;
+                }
+                let pair = value_data[pair_index].data.as_ref();
+                debug_assert!(pair.is_some(), "dangling cross-index: the paired bucket is empty");
+                pair
+            })
+            .map(|&(ref value, ..)| value)
+            .next()
+    }
+
+    /// Removes a key from the key_data section of the hashmap, and removes the value from the
+    /// value_data section of the hashmap. Returns the value that is associated with the key, if it
+    /// exists. If it does, `forget` is called with the indices that the key and the value were
+    /// removed from.
+    fn remove<Q: ?Sized, K, V, KH, VH, F>(
+        key: &Q,
+        key_data: &mut [Bucket<K, usize, B>],
+        value_data: &mut [Bucket<V, usize, B>],
+        key_hasher: &KH,
+        value_hasher: &VH,
+        map_len: &mut usize,
+        forget: F,
+    ) -> Option<(K, V)>
+    where
+        Q: Hash + Eq,
+        K: Hash + Eq + Borrow<Q>,
+        V: Hash,
+        KH: BuildHasher,
+        VH: BuildHasher,
+        F: FnOnce(usize, usize),
+    {
+        let len = key_data.len();
+        if len == 0 {
+            // these are the buckets of a shrink that isn't in progress
+            return None;
+        }
+        let index = Self::find_ideal_index(&key, key_hasher, len);
+
+        let neighbourhood = key_data[index].neighbourhood;
+        if neighbourhood.empty() {
+            return None;
+        }
+
+        if let Some(offset) = neighbourhood.iter().find(|offset| {
+            match key_data[(index + offset) % len].data {
+                Some((ref candidate_key, ..)) => candidate_key.borrow() == key,
+                _ => false,
+            }
+        }) {
+            key_data[index].neighbourhood = neighbourhood & B::zero_at(offset);
+            let (key, value_index, _) = key_data[(index + offset) % len].data.take().unwrap();
+            debug_assert!(
+                value_data[value_index].data.is_some(),
+                "dangling cross-index: the paired bucket is empty"
+            );
+            let (value, ..) = value_data[value_index].data.take().unwrap();
+
+            let ideal_value_index = Self::find_ideal_index(&value, value_hasher, len);
+            let value_offset = (value_index + len - ideal_value_index) % len;
+
+            value_data[ideal_value_index].neighbourhood =
+                value_data[ideal_value_index].neighbourhood & B::zero_at(value_offset);
+
+            *map_len -= 1;
+            forget((index + offset) % len, value_index);
+
+            Some((key, value))
+        } else {
+            None
+        }
+    }
+
+    /// Removes the pair holding a left key, in the same way as `remove`, whether it is in the
+    /// current buckets or in the buckets of a shrink in progress. If the left key is incomplete,
+    /// it is also removed, although there is no pair to return for it.
+    fn remove_by_left<Q: ?Sized>(&mut self, left: &Q) -> Option<(L, R)>
+    where
+        L: Borrow<Q>,
+        Q: Hash + Eq,
+    {
+        if self.incomplete != 0 && self.forget_incomplete(left).is_some() {
+            return None;
+        }
+
+        let &mut BiMap {
+            ref mut len,
+            ref mut left_data,
+            ref mut right_data,
+            ref mut old_len,
+            ref mut old_left_data,
+            ref mut old_right_data,
+            ref left_hasher,
+            ref right_hasher,
+            ref mut order,
+            ..
+        } = self;
+        Self::remove(
+            left,
+            left_data,
+            right_data,
+            left_hasher,
+            right_hasher,
+            len,
+            |left_index, _| order.remove(left_index),
+        ).or_else(|| {
+            Self::remove(
+                left,
+                old_left_data,
+                old_right_data,
+                left_hasher,
+                right_hasher,
+                old_len,
+                |_, _| (),
+            )
+        })
+    }
+
+    /// Removes the pair holding a right key, in the same way as `remove_by_left`.
+    fn remove_by_right<Q: ?Sized>(&mut self, right: &Q) -> Option<(R, L)>
+    where
+        R: Borrow<Q>,
+        Q: Hash + Eq,
+    {
+        let &mut BiMap {
+            ref mut len,
+            ref mut left_data,
+            ref mut right_data,
+            ref mut old_len,
+            ref mut old_left_data,
+            ref mut old_right_data,
+            ref left_hasher,
+            ref right_hasher,
+            ref mut order,
+            ..
+        } = self;
+        Self::remove(
+            right,
+            right_data,
+            left_data,
+            right_hasher,
+            left_hasher,
+            len,
+            |_, left_index| order.remove(left_index),
+        ).or_else(|| {
+            Self::remove(
+                right,
+                old_right_data,
+                old_left_data,
+                right_hasher,
+                left_hasher,
+                old_len,
+                |_, _| (),
+            )
+        })
+    }
+
+    /// Gets a key from the left of the hashmap. Returns the value from the right of the hashmap
+    /// that associates with this key, if it exists.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// assert_eq!(None, map.get_left("Hello"));
+    ///
+    /// map.insert("Hello", 5);
+    /// assert_eq!(Some(&5), map.get_left("Hello"));
+    /// ```
+    pub fn get_left<'a, Q: ?Sized>(&'a self, left: &Q) -> Option<&'a R>
+    where
+        L: Borrow<Q>,
+        Q: Hash + Eq,
+    {
+        let &BiMap {
+            ref left_data,
+            ref right_data,
+            ref old_left_data,
+            ref old_right_data,
+            ref left_hasher,
+            ..
+        } = self;
+        Self::get(left, left_data, right_data, left_hasher)
+            .or_else(|| Self::get(left, old_left_data, old_right_data, left_hasher))
+    }
+
+    /// Gets a key from the right of the hashmap. Returns the value from the left of the hashmap
+    /// that associates with this key, if it exists.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// assert_eq!(None, map.get_right(&5));
+    ///
+    /// map.insert("Hello", 5);
+    /// assert_eq!(Some(&"Hello"), map.get_right(&5));
+    /// ```
+    pub fn get_right<'a, Q: ?Sized>(&'a self, right: &Q) -> Option<&'a L>
+    where
+        R: Borrow<Q>,
+        Q: Hash + Eq,
+    {
+        let &BiMap {
+            ref right_data,
+            ref left_data,
+            ref old_right_data,
+            ref old_left_data,
+            ref right_hasher,
+            ..
+        } = self;
+        Self::get(right, right_data, left_data, right_hasher)
+            .or_else(|| Self::get(right, old_right_data, old_left_data, right_hasher))
+    }
+
+    /// Gets a key from the left of the hashmap, in the same way as `get_left`, but returns an error
+    /// saying why if there is no value from the right of the hashmap that associates with it:
+    /// either because the hashmap is empty, or because the key isn't in it.
+    ///
+    /// ```
+    /// # use isomorphism::{BiMap, ResolveError};
+    /// let mut map = BiMap::new();
+    /// assert_eq!(Err(ResolveError::EmptyMap), map.resolve_left("Hello"));
+    ///
+    /// map.insert("Hello", 5);
+    /// assert_eq!(Ok(&5), map.resolve_left("Hello"));
+    /// assert_eq!(Err(ResolveError::NotFound), map.resolve_left("World"));
+    /// ```
+    pub fn resolve_left<Q: ?Sized>(&self, left: &Q) -> Result<&R, ResolveError>
+    where
+        L: Borrow<Q>,
+        Q: Hash + Eq,
+    {
+        if self.is_empty() {
+            Err(ResolveError::EmptyMap)
+        } else {
+            self.get_left(left).ok_or(ResolveError::NotFound)
+        }
+    }
+
+    /// Gets a key from the left of the hashmap. Returns the left key that is stored in the
+    /// hashmap and is equal to this key, if it exists, rather than the value it associates with.
+    /// This is useful for sharing one copy of each key between several hashmaps, such as when
+    /// interning them. Incomplete left keys (see `insert_left_only`) are found too.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert(String::from("Hello"), 5);
+    ///
+    /// let stored = map.canonical_left("Hello").unwrap();
+    /// assert_eq!("Hello", stored);
+    /// assert_eq!(None, map.canonical_left("World"));
+    /// ```
+    pub fn canonical_left<Q: ?Sized>(&self, left: &Q) -> Option<&L>
+    where
+        L: Borrow<Q>,
+        Q: Hash + Eq,
+    {
+        let (left_data, _, index) = self.buckets_at(self.find_left(left)?);
+        left_data[index].data.as_ref().map(|&(ref left, ..)| left)
+    }
+
+    /// Gets a key from the left of the hashmap, returning mutable access to the value from the
+    /// right of the hashmap that associates with this key, if it exists. The right value must not
+    /// be modified in a way that changes its hash or equality: see `RightGuard` for how this is
+    /// checked when debug assertions are enabled.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// use std::hash::{Hash, Hasher};
+    ///
+    /// // accounts are identified by their ids alone
+    /// #[derive(Debug)]
+    /// struct Account {
+    ///     id: u64,
+    ///     balance: u64,
+    /// }
+    ///
+    /// impl PartialEq for Account {
+    ///     fn eq(&self, other: &Self) -> bool {
+    ///         self.id == other.id
+    ///     }
+    /// }
+    ///
+    /// impl Eq for Account {}
+    ///
+    /// impl Hash for Account {
+    ///     fn hash<H: Hasher>(&self, state: &mut H) {
+    ///         self.id.hash(state);
+    ///     }
+    /// }
+    ///
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", Account { id: 5, balance: 100 });
+    ///
+    /// map.borrow_right_mut("Hello").unwrap().balance += 20;
+    /// assert_eq!(120, map.get_left("Hello").unwrap().balance);
+    /// assert!(map.borrow_right_mut("World").is_none());
+    /// ```
+    pub fn borrow_right_mut<Q: ?Sized>(&mut self, left: &Q) -> Option<RightGuard<'_, R, RH>>
+    where
+        L: Borrow<Q>,
+        Q: Hash + Eq,
+    {
+        self.finish_shrink();
+        let &mut BiMap {
+            ref left_data,
+            ref mut right_data,
+            ref left_hasher,
+            ref right_hasher,
+            ..
+        } = self;
+        let left_index = Self::find(left, left_data, left_hasher)?;
+        let (_, right_index) = left_data[left_index].pair()?;
+        let &mut (ref mut right, ..) = right_data[right_index].data.as_mut().unwrap();
+        Some(RightGuard::new(right, right_hasher))
+    }
+
+    /// Gets a key from the left of the hashmap, in the same way as `get_left`, and also returns how
+    /// far the key is from its ideal bucket. This is the number of buckets that would be examined
+    /// before finding it, were the neighbourhood searched one bucket at a time, so 0 means that
+    /// the key is in its ideal bucket. Averaging this over many lookups gives an idea of how well
+    /// the keys are spread out by the hasher.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    ///
+    /// let (right, probes) = map.get_left_probed("Hello").unwrap();
+    /// assert_eq!(&5, right);
+    /// assert!(probes < 32);
+    /// assert_eq!(None, map.get_left_probed("World"));
+    /// ```
+    pub fn get_left_probed<Q: ?Sized>(&self, left: &Q) -> Option<(&R, u32)>
+    where
+        L: Borrow<Q>,
+        Q: Hash + Eq,
+    {
+        let (left_data, right_data, index) = self.buckets_at(self.find_left(left)?);
+        let &(_, right_index, ideal) = left_data[index].data.as_ref().unwrap();
+        if right_index == INCOMPLETE {
+            return None;
+        }
+        let &(ref right, ..) = right_data[right_index].data.as_ref().unwrap();
+        let len = left_data.len();
+        Some((right, ((index + len - ideal) % len) as u32))
+    }
+
+    /// Returns the contents of every bucket within the neighbourhood of the ideal bucket of a left
+    /// key, in order of their distance from it, whether or not the key is in the hashmap. Buckets
+    /// holding a left key are given with the pair they belong to, even if the key has a different
+    /// ideal bucket, and empty buckets are given as None, as are incomplete left keys. This is
+    /// intended for debugging, to show how keys cluster together around a bucket. While a shrink
+    /// started by `shrink_incremental` is in progress, the buckets shown are those that hold the
+    /// key, or those it would be inserted into if it isn't in the hashmap.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    ///
+    /// let view = map.neighbourhood_view_left("Hello");
+    /// assert!(view.contains(&Some((&"Hello", &5))));
+    /// assert_eq!(1, view.iter().filter(|slot| slot.is_some()).count());
+    /// ```
+    pub fn neighbourhood_view_left<Q: ?Sized>(&self, left: &Q) -> Vec<Option<(&L, &R)>>
+    where
+        L: Borrow<Q>,
+        Q: Hash + Eq,
+    {
+        let (left_data, right_data, _) = self.buckets_at(self.find_left(left).unwrap_or(0));
+        let len = left_data.len();
+        let ideal = Self::find_ideal_index(&left, &self.left_hasher, len);
+        (0..cmp::min(B::size(), len))
+            .map(|offset| {
+                let (left, right_index) = left_data[(ideal + offset) % len].pair()?;
+                let &(ref right, ..) = right_data[right_index].data.as_ref().unwrap();
+                Some((left, right))
+            })
+            .collect()
+    }
+
+    /// Gets a key from the left of the hashmap by its hash, and a closure that picks it out from
+    /// any other left keys with a similar hash. Returns the value from the right of the hashmap
+    /// that associates with this key, if it exists. This allows a lookup without building a key of
+    /// type L, such as when the parts of a tuple key are held separately.
+    ///
+    /// The hash must be the one that the hasher for the left of the hashmap gives the key, which
+    /// can be found through `left_hasher`. Given any other hash, the key will most likely not be
+    /// found. The closure should only return true for the key being looked up. A hash computed
+    /// before the hasher was replaced (see `left_hasher`) counts as any other hash.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// use std::hash::{BuildHasher, Hash, Hasher};
+    ///
+    /// let mut map = BiMap::new();
+    /// map.insert((1u32, 2u32), "Hello");
+    ///
+    /// // a tuple hashes its parts in order
+    /// let (a, b) = (1u32, 2u32);
+    /// let mut hasher = map.left_hasher().build_hasher();
+    /// a.hash(&mut hasher);
+    /// b.hash(&mut hasher);
+    ///
+    /// let right = map.get_left_where(hasher.finish(), |&(x, y)| x == a && y == b);
+    /// assert_eq!(Some(&"Hello"), right);
+    /// ```
+    pub fn get_left_where<F: FnMut(&L) -> bool>(&self, hash: u64, mut eq: F) -> Option<&R> {
+        Self::get_by_hash(hash, &self.left_data, &self.right_data, &mut eq)
+            .or_else(|| Self::get_by_hash(hash, &self.old_left_data, &self.old_right_data, eq))
+    }
+
+    /// Gets the entry for a left key, which can be used to look up, modify or insert its pair in
+    /// place. An incomplete left key, which isn't paired with anything, has a vacant entry.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// # use std::hash::{Hash, Hasher};
+    /// // counters with an id, which are hashed and compared by their id alone
+    /// #[derive(Debug)]
+    /// struct Counter {
+    ///     id: u64,
+    ///     count: u64,
+    /// }
+    ///
+    /// impl PartialEq for Counter {
+    ///     fn eq(&self, other: &Self) -> bool {
+    ///         self.id == other.id
+    ///     }
+    /// }
+    ///
+    /// impl Eq for Counter {}
+    ///
+    /// impl Hash for Counter {
+    ///     fn hash<H: Hasher>(&self, state: &mut H) {
+    ///         self.id.hash(state);
+    ///     }
+    /// }
+    ///
+    /// let mut map: BiMap<&str, Counter> = BiMap::new();
+    /// for &(left, id) in &[("Hello", 1), ("World", 2), ("Hello", 1)] {
+    ///     map.entry_left(left)
+    ///         .and_modify(|counter| counter.count += 1)
+    ///         .or_insert(Counter { id, count: 1 });
+    /// }
+    /// assert_eq!(2, map.get_left("Hello").unwrap().count);
+    /// assert_eq!(1, map.get_left("World").unwrap().count);
+    /// ```
+    pub fn entry_left(&mut self, left: L) -> LeftEntry<'_, L, R, LH, RH, B> {
+        self.finish_shrink();
+        match Self::find(&left, &self.left_data, &self.left_hasher) {
+            Some(index) if self.left_data[index].pair().is_some() => {
+                LeftEntry::Occupied(OccupiedLeftEntry { map: self, index })
+            }
+            _ => LeftEntry::Vacant(VacantLeftEntry { map: self, left }),
+        }
+    }
+
+    /// Returns a cursor pointing at the first pair of the hashmap, in an arbitrary order, which
+    /// can step through the pairs one at a time and remove them as it goes.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map: BiMap<u64, u64> = (0..10).map(|i| (i, i)).collect();
+    ///
+    /// let mut cursor = map.left_cursor_mut();
+    /// loop {
+    ///     let even = match cursor.current() {
+    ///         Some((&left, _)) => left % 2 == 0,
+    ///         None => break,
+    ///     };
+    ///     if even {
+    ///         cursor.remove_current();
+    ///     } else {
+    ///         cursor.advance();
+    ///     }
+    /// }
+    /// assert_eq!(5, map.len());
+    /// assert_eq!(None, map.get_left(&4));
+    /// ```
+    pub fn left_cursor_mut(&mut self) -> LeftCursorMut<'_, L, R, LH, RH, B> {
+        self.finish_shrink();
+        let mut cursor = LeftCursorMut { map: self, index: 0 };
+        cursor.skip_empty();
+        cursor
+    }
+
+    /// Finds the differences between the pairs of this hashmap and those of another one: the
+    /// pairs that would have to be added, removed or changed for this hashmap to hold the same
+    /// pairs as the other. Pairs are matched up by their left keys, so a right value that moves
+    /// from one left key to another shows up as one pair removed and another added.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut old = BiMap::new();
+    /// old.insert("Hello", 1);
+    /// old.insert("World", 2);
+    ///
+    /// let mut new = BiMap::new();
+    /// new.insert("Hello", 3);
+    /// new.insert("Hashmaps", 4);
+    ///
+    /// let diff = old.diff(&new);
+    /// assert_eq!(vec![(&"Hashmaps", &4)], diff.added);
+    /// assert_eq!(vec![(&"World", &2)], diff.removed);
+    /// assert_eq!(vec![(&"Hello", &1, &3)], diff.changed);
+    /// ```
+    pub fn diff<'a, LH2, RH2, B2>(
+        &'a self,
+        other: &'a BiMap<L, R, LH2, RH2, B2>,
+    ) -> Diff<&'a L, &'a R>
+    where
+        LH2: BuildHasher,
+        RH2: BuildHasher,
+        B2: BitField,
+    {
+        let mut diff = Diff::default();
+        for (left, right) in self {
+            match other.get_left(left) {
+                Some(other_right) if other_right != right => {
+                    diff.changed.push((left, right, other_right))
+                }
+                Some(_) => {}
+                None => diff.removed.push((left, right)),
+            }
+        }
+        diff.added = other.iter().filter(|&(left, _)| self.get_left(left).is_none()).collect();
+        diff
+    }
+
+    /// Applies a diff to the hashmap, so that if the diff was found between this hashmap and
+    /// another one, this hashmap ends up holding the same pairs as the other. Every removal is
+    /// applied first, by left key, so that the right values they free up can be given to the
+    /// pairs that are added or changed. Those pairs are then inserted as with `insert`, which
+    /// removes any pair still holding one of their keys, so every key stays in a single pair
+    /// throughout.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut old = BiMap::new();
+    /// old.insert("Hello", 1);
+    /// old.insert("World", 2);
+    ///
+    /// let mut new = BiMap::new();
+    /// new.insert("Hello", 2);
+    /// new.insert("Hashmaps", 1);
+    ///
+    /// let diff = old.diff(&new).cloned();
+    /// old.apply_diff(diff);
+    /// assert_eq!(old, new);
+    /// ```
+    pub fn apply_diff(&mut self, diff: Diff<L, R>) {
+        let Diff {
+            added,
+            removed,
+            changed,
+        } = diff;
+        for (left, _) in removed {
+            self.remove_left(&left);
+        }
+        for (left, _, right) in changed {
+            self.insert(left, right);
+        }
+        for (left, right) in added {
+            self.insert(left, right);
+        }
+    }
+
+    /// Looks up a sequence of keys from the left of the hashmap. Each key is returned alongside
+    /// the value from the right of the hashmap that associates with it, if it exists, so that the
+    /// results stay associated with the inputs that produced them.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    /// map.insert("World", 7);
+    ///
+    /// let results: Vec<_> = map.get_left_zip(&["World", "Nope", "Hello"]).collect();
+    /// assert_eq!(
+    ///     vec![(&"World", Some(&7)), (&"Nope", None), (&"Hello", Some(&5))],
+    ///     results
+    /// );
+    /// ```
+    pub fn get_left_zip<'a, Q: ?Sized + 'a, I>(
+        &'a self,
+        keys: I,
+    ) -> impl Iterator<Item = (&'a Q, Option<&'a R>)> + 'a
+    where
+        L: Borrow<Q>,
+        Q: Hash + Eq,
+        I: IntoIterator<Item = &'a Q>,
+        I::IntoIter: 'a,
+    {
+        keys.into_iter().map(move |key| (key, self.get_left(key)))
+    }
+
+    /// Removes a key from the left of the hashmap. Returns the value from the right of the hashmap
+    /// that was associated with this key, if it existed. Will remove both the left and right sides
+    /// of the pair, if it exists, meaning that `get_right` will no longer work for the value
+    /// associated with the key that is removed. An incomplete left key (see `insert_left_only`)
+    /// is also removed, although there is no right value to return for it.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    ///
+    /// assert_eq!(Some(&5), map.get_left("Hello"));
+    /// assert_eq!(Some(&"Hello"), map.get_right(&5));
+    ///
+    /// let old = map.remove_left("Hello");
+    /// assert_eq!(Some(5), old);
+    ///
+    /// assert_eq!(None, map.get_left("Hello"));
+    /// assert_eq!(None, map.get_right(&5));
+    /// ```
+    pub fn remove_left<Q: ?Sized>(&mut self, left: &Q) -> Option<R>
+    where
+        L: Borrow<Q>,
+        Q: Hash + Eq,
+    {
+        let output = self.remove_by_left(left).map(|(_key, value)| value);
+        if output.is_some() {
+            self.modifications += 1;
+        }
+        output
+    }
+
+    /// Removes a key from the left of the hashmap, in the same way as `remove_left`, but only if
+    /// `pred` returns true for the value from the right of the hashmap that it associates with.
+    /// Returns that value if the pair was removed. If `pred` returns false, the pair is left as it
+    /// was. Incomplete left keys have no right value to test, so they are never removed.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    ///
+    /// assert_eq!(None, map.remove_left_if("Hello", |&right| right > 10));
+    /// assert_eq!(Some(&5), map.get_left("Hello"));
+    ///
+    /// assert_eq!(Some(5), map.remove_left_if("Hello", |&right| right < 10));
+    /// assert_eq!(None, map.get_right(&5));
+    /// ```
+    pub fn remove_left_if<Q: ?Sized, F>(&mut self, left: &Q, pred: F) -> Option<R>
+    where
+        L: Borrow<Q>,
+        Q: Hash + Eq,
+        F: FnOnce(&R) -> bool,
+    {
+        let left_index = self.find_left(left)?;
+        let (left_data, right_data, index) = self.buckets_at(left_index);
+        let (_, right_index) = left_data[index].pair()?;
+        let &(ref right, ..) = right_data[right_index].data.as_ref().unwrap();
+        if pred(right) {
+            Some(self.remove_at(left_index).1)
+        } else {
+            None
+        }
+    }
+
+    /// Removes a key from the right of the hashmap. Returns the value from the left of the hashmap
+    /// that was associated with this key, if it existed. Will remove both the left and right sides
+    /// of the pair, if it exists, meaning that `get_left` will no longer work for the value
+    /// associated with the key that is removed.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    ///
+    /// assert_eq!(Some(&5), map.get_left("Hello"));
+    /// assert_eq!(Some(&"Hello"), map.get_right(&5));
+    ///
+    /// let old = map.remove_right(&5);
+    /// assert_eq!(Some("Hello"), old);
+    ///
+    /// assert_eq!(None, map.get_left("Hello"));
+    /// assert_eq!(None, map.get_right(&5));
+    /// ```
+    pub fn remove_right<Q: ?Sized>(&mut self, right: &Q) -> Option<L>
+    where
+        R: Borrow<Q>,
+        Q: Hash + Eq,
+    {
+        let output = self.remove_by_right(right).map(|(_key, value)| value);
+        if output.is_some() {
+            self.modifications += 1;
+        }
+        output
+    }
 
-        // attempt to insert, hold onto the keys if it fails
-        let failure: Option<(L, R)> = if MAX_LOAD_FACTOR * self.len as f32
-            >= self.left_data.len() as f32
-        {
-            Some((left, right))
-        } else {
-            let &mut BiMap {
-                ref mut left_data,
-                ref mut right_data,
-                ref left_hasher,
-                ref right_hasher,
-                ..
-            } = self;
-            match Self::insert_one_sided(left, left_data, right_data, left_hasher) {
-                Ok(left_index) => {
-                    match Self::insert_one_sided(right, right_data, left_data, right_hasher) {
-                        Ok(right_index) => {
-                            let &mut (_, ref mut paired_right_index, _) =
-                                left_data[left_index].data.as_mut().unwrap();
-                            *paired_right_index = right_index;
+    /// Removes a left key from the hashmap if it is incomplete, returning it. Does nothing if the
+    /// left key is paired with a right key, or isn't in the hashmap.
+    fn forget_incomplete<Q: ?Sized>(&mut self, left: &Q) -> Option<L>
+    where
+        L: Borrow<Q>,
+        Q: Hash + Eq,
+    {
+        let left_index = self.find_left(left)?;
+        let (left_data, _, index) = self.buckets_at(left_index);
+        if left_data[index].pair().is_some() {
+            return None;
+        }
+        Some(self.take_incomplete(left_index))
+    }
 
-                            let &mut (_, ref mut paired_left_index, _) =
-                                right_data[right_index].data.as_mut().unwrap();
-                            *paired_left_index = left_index;
-                            None
-                        }
-                        Err(right) => {
-                            let (left, _, left_ideal) = left_data[left_index].data.take().unwrap();
-                            Self::mark_as_empty(left_ideal, left_index, left_data);
-                            Some((left, right))
-                        }
-                    }
-                }
-                Err(left) => Some((left, right)),
-            }
+    /// Removes the incomplete left key stored at a given index within the hashmap, counting on
+    /// into the buckets of a shrink in progress as for `buckets_at`.
+    fn take_incomplete(&mut self, left_index: usize) -> L {
+        let (left_data, len, index) = if left_index < self.left_data.len() {
+            (&mut self.left_data, &mut self.len, left_index)
+        } else {
+            let index = left_index - self.left_data.len();
+            (&mut self.old_left_data, &mut self.old_len, index)
         };
+        let (left, _, ideal) = left_data[index].data.take().unwrap();
+        Self::mark_as_empty(ideal, index, left_data);
+        *len -= 1;
+        self.incomplete -= 1;
+        left
+    }
 
-        if failure.is_none() {
-            self.len += 1;
+    /// Inserts a left key into the hashmap without a right key to pair it with, as the first half
+    /// of a two phase insert. The pair can later be finished with `complete_left`. Until then, the
+    /// left key is reported by `contains_left` and `incomplete_lefts`, but is otherwise treated as
+    /// if it weren't in the hashmap: `get_left` does not find it, and it isn't counted by `len` or
+    /// visited by `iter`. If the left key was already paired with a right key, that pair is
+    /// removed and its right key is returned.
+    ///
+    /// Incomplete left keys are stored in the buckets alongside the others, so they are looked up
+    /// as quickly, and take up room in the hashmap until they are completed or removed. As they
+    /// aren't pairs, everything that works with the pairs of the hashmap skips them: equality,
+    /// ordering, `Hash`, `content_hash`, `Debug` and serialization only see the complete pairs.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map: BiMap<&str, u64> = BiMap::new();
+    /// map.insert_left_only("Hello");
+    ///
+    /// assert!(map.contains_left("Hello"));
+    /// assert_eq!(None, map.get_left("Hello"));
+    /// assert_eq!(0, map.len());
+    /// ```
+    pub fn insert_left_only(&mut self, left: L) -> Option<R> {
+        let old_right = self.remove_left(&left);
+        self.insert_incomplete(left);
+        old_right
+    }
+
+    /// Completes a two phase insert started by `insert_left_only`, by pairing an incomplete left
+    /// key with a right key. Returns the left key that was previously associated with the right
+    /// key, if it existed, in the same way as `insert`. If the left key was not incomplete, the
+    /// hashmap is left unmodified and the right key is given back as an error.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map: BiMap<&str, u64> = BiMap::new();
+    /// map.insert_left_only("Hello");
+    ///
+    /// assert_eq!(Ok(None), map.complete_left("Hello", 5));
+    /// assert_eq!(Some(&5), map.get_left("Hello"));
+    ///
+    /// // "Hello" is no longer incomplete
+    /// assert_eq!(Err(7), map.complete_left("Hello", 7));
+    /// ```
+    pub fn complete_left<Q: ?Sized>(&mut self, left: &Q, right: R) -> Result<Option<L>, R>
+    where
+        L: Borrow<Q>,
+        Q: Hash + Eq,
+    {
+        match self.forget_incomplete(left) {
+            Some(left) => Ok(self.insert(left, right).1),
+            None => Err(right),
         }
+    }
 
+    /// Returns true if the left key is in the hashmap, whether it is paired with a right key or is
+    /// incomplete.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map: BiMap<&str, u64> = BiMap::new();
+    /// map.insert("Hello", 5);
+    /// map.insert_left_only("World");
+    ///
+    /// assert!(map.contains_left("Hello"));
+    /// assert!(map.contains_left("World"));
+    /// assert!(!map.contains_left("Nope"));
+    /// ```
+    pub fn contains_left<Q: ?Sized>(&self, left: &Q) -> bool
+    where
+        L: Borrow<Q>,
+        Q: Hash + Eq,
+    {
+        self.find_left(left).is_some()
+    }
 
-        if let Some((left, right)) = failure {
-            // resize, as we were unable to insert
-            self.len = 0;
-            let capacity = self.left_data.len() * RESIZE_GROWTH_FACTOR;
-            let old_left_data = mem::replace(&mut self.left_data, Bucket::empty_vec(capacity));
-            let old_right_data = mem::replace(&mut self.right_data, Bucket::empty_vec(capacity));
+    /// An iterator visiting all of the left keys that have been inserted with `insert_left_only`
+    /// but not yet completed, in an arbitrary order. As the incomplete left keys are stored among
+    /// the pairs, this looks through every bucket.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map: BiMap<&str, u64> = BiMap::new();
+    /// map.insert("Hello", 5);
+    /// map.insert_left_only("World");
+    ///
+    /// assert_eq!(vec![&"World"], map.incomplete_lefts().collect::<Vec<_>>());
+    /// ```
+    pub fn incomplete_lefts(&self) -> impl Iterator<Item = &L> {
+        self.left_data
+            .iter()
+            .chain(self.old_left_data.iter())
+            .filter(|bucket| bucket.pair().is_none())
+            .filter_map(|bucket| bucket.data.as_ref())
+            .map(|&(ref left, ..)| left)
+    }
 
-            iter::once((left, right))
-                .chain(IntoIter::new(old_left_data, old_right_data))
-                .for_each(|(left, right)| {
-                    self.insert(left, right);
-                });
+    /// Removes the pair whose left key is stored at a given index within the hashmap, counting
+    /// on into the buckets of a shrink in progress as for `buckets_at`. There must be a left key
+    /// stored at this index.
+    fn remove_at(&mut self, left_index: usize) -> (L, R) {
+        self.modifications += 1;
+        if left_index >= self.left_data.len() {
+            self.old_len -= 1;
+            let index = left_index - self.left_data.len();
+            return Self::take_pair(index, &mut self.old_left_data, &mut self.old_right_data);
         }
 
-        output
+        let &mut BiMap {
+            ref mut len,
+            ref mut left_data,
+            ref mut right_data,
+            ref mut order,
+            ..
+        } = self;
+
+        let pair = Self::take_pair(left_index, left_data, right_data);
+        *len -= 1;
+        order.remove(left_index);
+
+        pair
     }
 
-    /// Looks up a key in the key_data section of the hashap, and if it exists returns it from the
-    /// value_data section of the hashap. Returns the value that is associated with the key, if it
-    /// exists.
-    fn get<'a, Q: ?Sized, K, V, KH>(
+    /// Takes the pair whose left key is stored at a given index out of a set of buckets, and marks
+    /// both of its buckets as empty. There must be a left key stored at this index.
+    fn take_pair(
+        left_index: usize,
+        left_data: &mut [Bucket<L, usize, B>],
+        right_data: &mut [Bucket<R, usize, B>],
+    ) -> (L, R) {
+        let (left, right_index, left_ideal) = left_data[left_index].data.take().unwrap();
+        Self::mark_as_empty(left_ideal, left_index, left_data);
+        let (right, _, right_ideal) = right_data[right_index].data.take().unwrap();
+        Self::mark_as_empty(right_ideal, right_index, right_data);
+
+        (left, right)
+    }
+
+    /// Finds the index of a left key within the hashmap, counting on into the buckets of a shrink
+    /// in progress as for `buckets_at`, if it exists.
+    fn find_left<Q: ?Sized>(&self, left: &Q) -> Option<usize>
+    where
+        L: Borrow<Q>,
+        Q: Hash + Eq,
+    {
+        Self::find(left, &self.left_data, &self.left_hasher).or_else(|| {
+            let index = Self::find(left, &self.old_left_data, &self.left_hasher)?;
+            Some(self.left_data.len() + index)
+        })
+    }
+
+    /// Finds the index of a key within the key_data section of the hashmap, if it exists.
+    fn find<Q: ?Sized, K, KH>(
         key: &Q,
         key_data: &[Bucket<K, usize, B>],
-        value_data: &'a [Bucket<V, usize, B>],
         key_hasher: &KH,
-    ) -> Option<&'a V>
+    ) -> Option<usize>
     where
         Q: Hash + Eq,
         K: Hash + Eq + Borrow<Q>,
         KH: BuildHasher,
     {
         let len = key_data.len();
+        if len == 0 {
+            // these are the buckets of a shrink that isn't in progress
+            return None;
+        }
         let ideal = Self::find_ideal_index(&key, key_hasher, len);
 
         let neighbourhood = key_data[ideal].neighbourhood;
+        if neighbourhood.empty() {
+            return None;
+        }
+
         neighbourhood
             .iter()
-            .filter_map(|offset| key_data[(ideal + offset) % len].data.as_ref())
-            .filter(|&&(ref candidate_key, ..)| candidate_key.borrow() == key)
-            .filter_map(|&(_, pair_index, _)| value_data[pair_index].data.as_ref())
-            .map(|&(ref value, ..)| value)
-            .next()
+            .map(|offset| (ideal + offset) % len)
+            .find(|&index| match key_data[index].data {
+                Some((ref candidate_key, ..)) => candidate_key.borrow() == key,
+                None => false,
+            })
     }
 
-    /// Removes a key from the key_data section of the hashmap, and removes the value from the
-    /// value_data section of the hashmap. Returns the value that is associated with the key, if it
-    /// exists.
-    fn remove<Q: ?Sized, K, V, KH, VH>(
-        key: &Q,
-        key_data: &mut [Bucket<K, usize, B>],
-        value_data: &mut [Bucket<V, usize, B>],
-        key_hasher: &KH,
-        value_hasher: &VH,
-        map_len: &mut usize,
-    ) -> Option<(K, V)>
+    /// Looks up a key from the left of the hashmap, returning a handle to its pair if it exists.
+    /// The handle can later be given to `remove_by_handle` to remove the pair without hashing
+    /// either of its keys again.
+    ///
+    /// The handle stays valid across inserts and removals of other pairs, until the hashmap is
+    /// resized or otherwise has to move existing pairs to different buckets. Once the pair it was
+    /// taken from is removed, the handle is rejected, even if another pair is later stored in the
+    /// same buckets.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    ///
+    /// let handle = map.handle_left("Hello").unwrap();
+    /// assert_eq!(None, map.handle_left("World"));
+    /// assert_eq!(Some(("Hello", 5)), map.remove_by_handle(handle));
+    /// assert!(map.is_empty());
+    /// ```
+    pub fn handle_left<Q: ?Sized>(&self, left: &Q) -> Option<Handle>
     where
+        L: Borrow<Q>,
         Q: Hash + Eq,
-        K: Hash + Eq + Borrow<Q>,
-        V: Hash,
-        KH: BuildHasher,
-        VH: BuildHasher,
     {
-        let len = key_data.len();
-        let index = Self::find_ideal_index(&key, key_hasher, len);
+        let left_index = self.find_left(left)?;
+        let (left_data, _, index) = self.buckets_at(left_index);
+        let (_, right_index) = left_data[index].pair()?;
+        Some(Handle {
+            left_index,
+            // the right index counts on into the buckets of a shrink in the same way
+            right_index: left_index - index + right_index,
+            generation: self.layout_generation,
+            left_generation: left_data[index].generation,
+        })
+    }
 
-        let neighbourhood = key_data[index].neighbourhood;
-        if let Some(offset) = neighbourhood.iter().find(|offset| {
-            match key_data[(index + offset) % len].data {
-                Some((ref candidate_key, ..)) => candidate_key.borrow() == key,
-                _ => false,
+    /// Removes the pair referred to by a handle from `handle_left`, without hashing either of its
+    /// keys. Returns None, leaving the hashmap unmodified, if the handle is from an older layout of
+    /// the hashmap (such as from before a resize) or no longer refers to a pair.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map = BiMap::new();
+    /// map.insert("Hello", 5);
+    ///
+    /// let handle = map.handle_left("Hello").unwrap();
+    /// map.insert("World", 7);
+    /// assert_eq!(Some(("Hello", 5)), map.remove_by_handle(handle));
+    ///
+    /// // the pair has already been removed
+    /// assert_eq!(None, map.remove_by_handle(handle));
+    /// ```
+    pub fn remove_by_handle(&mut self, handle: Handle) -> Option<(L, R)> {
+        let Handle {
+            left_index,
+            right_index,
+            generation,
+            left_generation,
+        } = handle;
+
+        let (left_data, right_data, index) = self.buckets_at(left_index);
+        let first_index = left_index - index;
+        if generation != self.layout_generation
+            || index >= left_data.len()
+            || right_index < first_index
+            || right_index - first_index >= right_data.len()
+            || left_generation != left_data[index].generation
+        {
+            return None;
+        }
+
+        let paired = match left_data[index].pair() {
+            Some((_, paired_right_index)) if first_index + paired_right_index == right_index => {
+                match right_data[paired_right_index].data {
+                    Some((_, paired_left_index, _)) => paired_left_index == index,
+                    None => false,
+                }
             }
-        }) {
-            key_data[index].neighbourhood = neighbourhood & B::zero_at(offset);
-            let (key, value_index, _) = key_data[(index + offset) % len].data.take().unwrap();
-            let (value, ..) = value_data[value_index].data.take().unwrap();
+            _ => false,
+        };
 
-            let ideal_value_index = Self::find_ideal_index(&value, value_hasher, len);
-            let value_offset = (value_index + len - ideal_value_index) % len;
+        if paired {
+            Some(self.remove_at(left_index))
+        } else {
+            None
+        }
+    }
 
-            value_data[ideal_value_index].neighbourhood =
-                value_data[ideal_value_index].neighbourhood & B::zero_at(value_offset);
+    /// Retains only the pairs specified by the predicate. In other words, removes every pair for
+    /// which `f(&left, &right)` returns false. If the hashmap keeps track of its insertion order,
+    /// the pairs that are retained keep their order relative to each other.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// let mut map: BiMap<u64, char> = BiMap::new();
+    /// map.insert(1, 'a');
+    /// map.insert(2, 'b');
+    /// map.insert(3, 'c');
+    ///
+    /// map.retain(|&left, _| left % 2 == 1);
+    /// assert_eq!(2, map.len());
+    /// assert_eq!(None, map.get_left(&2));
+    /// assert_eq!(None, map.get_right(&'b'));
+    /// ```
+    pub fn retain<F: FnMut(&L, &R) -> bool>(&mut self, mut f: F) {
+        self.retain_until(|left, right| {
+            if f(left, right) {
+                RetainControl::Keep
+            } else {
+                RetainControl::Remove
+            }
+        });
+    }
 
-            *map_len -= 1;
+    /// Visits the pairs of the hashmap in an arbitrary order, keeping or removing each one as
+    /// `f(&left, &right)` says, until it returns `RetainControl::Stop`. Every pair that hadn't
+    /// been visited by then is kept, as is the pair that `f` stopped on. This allows a large
+    /// hashmap to be cleaned up a bounded amount at a time. As with `retain`, the pairs that are
+    /// kept keep their insertion order.
+    ///
+    /// ```
+    /// # use isomorphism::{BiMap, RetainControl};
+    /// let mut map: BiMap<u64, u64> = (0..100).map(|i| (i, i)).collect();
+    ///
+    /// // remove at most 10 pairs
+    /// let mut removed = 0;
+    /// map.retain_until(|_, _| {
+    ///     if removed == 10 {
+    ///         RetainControl::Stop
+    ///     } else {
+    ///         removed += 1;
+    ///         RetainControl::Remove
+    ///     }
+    /// });
+    /// assert_eq!(90, map.len());
+    /// ```
+    pub fn retain_until<F: FnMut(&L, &R) -> RetainControl>(&mut self, mut f: F) {
+        self.finish_shrink();
+        for index in 0..self.left_data.len() {
+            let control = match self.left_data[index].pair() {
+                Some((left, right_index)) => {
+                    let &(ref right, ..) = self.right_data[right_index].data.as_ref().unwrap();
+                    f(left, right)
+                }
+                None => RetainControl::Keep,
+            };
 
-            Some((key, value))
-        } else {
-            None
+            match control {
+                RetainControl::Keep => {}
+                RetainControl::Remove => {
+                    self.remove_at(index);
+                }
+                RetainControl::Stop => break,
+            }
+        }
+
+        self.order.compact();
+    }
+
+    /// Retains only the pairs specified by the predicate, which is also given the chance to
+    /// modify the right value of each pair. Pairs for which `f(&left, &mut right)` returns false
+    /// are removed. As with `retain`, this is a single pass over the hashmap, and the pairs that
+    /// are kept keep their insertion order.
+    ///
+    /// As with `borrow_right_mut`, the right values must not be modified in a way that changes
+    /// their hash or equality: see `RightGuard` for how this is checked when debug assertions are
+    /// enabled.
+    ///
+    /// ```
+    /// # use isomorphism::BiMap;
+    /// # use std::hash::{Hash, Hasher};
+    /// // stock levels, which are hashed and compared by their item alone
+    /// struct Stock {
+    ///     item: &'static str,
+    ///     count: u64,
+    /// }
+    /// # impl PartialEq for Stock {
+    /// #     fn eq(&self, other: &Self) -> bool {
+    /// #         self.item == other.item
+    /// #     }
+    /// # }
+    /// # impl Eq for Stock {}
+    /// # impl Hash for Stock {
+    /// #     fn hash<H: Hasher>(&self, state: &mut H) {
+    /// #         self.item.hash(state);
+    /// #     }
+    /// # }
+    ///
+    /// let mut map = BiMap::new();
+    /// map.insert(1, Stock { item: "apples", count: 3 });
+    /// map.insert(2, Stock { item: "pears", count: 1 });
+    ///
+    /// // sell one of everything, and forget about anything that has sold out
+    /// map.retain_mut(|_, stock| {
+    ///     stock.count -= 1;
+    ///     stock.count > 0
+    /// });
+    /// assert_eq!(1, map.len());
+    /// assert_eq!(2, map.get_left(&1).unwrap().count);
+    /// ```
+    pub fn retain_mut<F: FnMut(&L, &mut R) -> bool>(&mut self, mut f: F) {
+        self.finish_shrink();
+        for index in 0..self.left_data.len() {
+            let &mut BiMap {
+                ref left_data,
+                ref mut right_data,
+                ref right_hasher,
+                ..
+            } = self;
+            let keep = match left_data[index].pair() {
+                Some((left, right_index)) => {
+                    let &mut (ref mut right, ..) = right_data[right_index].data.as_mut().unwrap();
+                    f(left, &mut RightGuard::new(right, right_hasher))
+                }
+                None => true,
+            };
+
+            if !keep {
+                self.remove_at(index);
+            }
+        }
+
+        self.order.compact();
+    }
+
+    /// Checks one side of the hashmap against the other, panicking if any of the internal
+    /// invariants of the hashmap do not hold. Returns the number of keys on this side, including
+    /// any incomplete left keys.
+    fn validate_one_sided<K: Hash, V, H: BuildHasher>(
+        key_data: &[Bucket<K, usize, B>],
+        value_data: &[Bucket<V, usize, B>],
+        hasher: &H,
+    ) -> usize {
+        let len = key_data.len();
+        assert_eq!(len, value_data.len(), "key and value arrays differ in length");
+
+        for (ideal, bucket) in key_data.iter().enumerate() {
+            for offset in bucket.neighbourhood.iter() {
+                assert!(offset < B::size(), "neighbourhood offset out of range");
+                let index = (ideal + offset) % len;
+                match key_data[index].data {
+                    Some((_, _, stored_ideal)) => assert_eq!(
+                        ideal, stored_ideal,
+                        "neighbourhood of {} marks index {} which belongs to {}",
+                        ideal, index, stored_ideal
+                    ),
+                    None => panic!("neighbourhood of {} marks empty index {}", ideal, index),
+                }
+            }
+        }
+
+        let mut count = 0;
+        for (index, bucket) in key_data.iter().enumerate() {
+            if let Some((ref key, pair_index, ideal)) = bucket.data {
+                count += 1;
+                assert_eq!(
+                    Self::find_ideal_index(key, hasher, len),
+                    ideal,
+                    "key at index {} has the wrong ideal index",
+                    index
+                );
+
+                let offset = (len + index - ideal) % len;
+                assert!(
+                    offset < B::size(),
+                    "key at index {} is outside its neighbourhood",
+                    index
+                );
+                assert!(
+                    key_data[ideal].neighbourhood.iter().any(|bit| bit == offset),
+                    "key at index {} is not marked in its neighbourhood",
+                    index
+                );
+
+                if pair_index == INCOMPLETE {
+                    // an incomplete left key has no pair to check
+                    continue;
+                }
+                assert!(pair_index < len, "key at index {} has a dangling pair index", index);
+                match value_data[pair_index].data {
+                    Some((_, back_index, _)) => assert_eq!(
+                        index, back_index,
+                        "key at index {} is paired with a value paired with index {}",
+                        index, back_index
+                    ),
+                    None => panic!("key at index {} is paired with an empty bucket", index),
+                }
+            }
         }
+
+        count
     }
 
-    /// Gets a key from the left of the hashmap. Returns the value from the right of the hashmap
-    /// that associates with this key, if it exists.
-    ///
-    /// ```
-    /// # use isomorphism::BiMap;
-    /// let mut map = BiMap::new();
-    /// assert_eq!(None, map.get_left("Hello"));
-    ///
-    /// map.insert("Hello", 5);
-    /// assert_eq!(Some(&5), map.get_left("Hello"));
-    /// ```
-    pub fn get_left<'a, Q: ?Sized>(&'a self, left: &Q) -> Option<&'a R>
-    where
-        L: Borrow<Q>,
-        Q: Hash + Eq,
-    {
+    /// Checks the internal invariants of the hashmap, panicking if any of them do not hold. This
+    /// is only intended for use in testing the hashmap itself.
+    #[doc(hidden)]
+    pub fn validate(&self) {
         let &BiMap {
+            len,
             ref left_data,
+            ref old_left_data,
+            incomplete,
             ref right_data,
+            ref old_right_data,
+            old_len,
+            old_next,
             ref left_hasher,
+            ref right_hasher,
+            ref order,
+            lru_capacity,
             ..
         } = self;
-        Self::get(left, left_data, right_data, left_hasher)
+
+        let unpaired = |left_data: &Buckets<L, B>| {
+            left_data
+                .iter()
+                .filter(|bucket| bucket.data.is_some() && bucket.pair().is_none())
+                .count()
+        };
+        let (unpaired, old_unpaired) = (unpaired(left_data), unpaired(old_left_data));
+        assert_eq!(incomplete, unpaired + old_unpaired, "wrong number of incomplete left keys");
+
+        let left_len = Self::validate_one_sided(left_data, right_data, left_hasher);
+        let right_len = Self::validate_one_sided(right_data, left_data, right_hasher);
+        assert_eq!(len, left_len, "wrong number of left keys");
+        assert_eq!(len - unpaired, right_len, "wrong number of right keys");
+        let old_left_len = Self::validate_one_sided(old_left_data, old_right_data, left_hasher);
+        let old_right_len = Self::validate_one_sided(old_right_data, old_left_data, right_hasher);
+        assert_eq!(old_len, old_left_len, "wrong number of left keys being shrunk");
+        assert_eq!(
+            old_len - old_unpaired,
+            old_right_len,
+            "wrong number of right keys being shrunk"
+        );
+        assert!(
+            right_data.iter().chain(old_right_data.iter()).all(|bucket| {
+                bucket.data.is_none() || bucket.pair().is_some()
+            }),
+            "a right key isn't paired with anything"
+        );
+        assert!(
+            old_left_data[..old_next].iter().all(|bucket| bucket.data.is_none()),
+            "a pair was skipped by the shrink"
+        );
+        order.validate(left_data);
+        if let Some(lru_capacity) = lru_capacity {
+            assert!(self.len() <= lru_capacity, "more pairs than the LRU capacity");
+        }
     }
 
-    /// Gets a key from the right of the hashmap. Returns the value from the left of the hashmap
-    /// that associates with this key, if it exists.
+    /// Inserts clones of every pair in a slice into the hashmap, as if by `insert`. As with
+    /// `extend`, if the hashmap needs to grow it is resized once, before any of the pairs are
+    /// inserted, straight to the size it would otherwise have grown to one resize at a time.
     ///
     /// ```
     /// # use isomorphism::BiMap;
     /// let mut map = BiMap::new();
-    /// assert_eq!(None, map.get_right(&5));
+    /// map.extend_from_slice(&[("Hello", 5), ("World", 7)]);
     ///
-    /// map.insert("Hello", 5);
-    /// assert_eq!(Some(&"Hello"), map.get_right(&5));
+    /// assert_eq!(2, map.len());
+    /// assert_eq!(Some(&7), map.get_left("World"));
     /// ```
-    pub fn get_right<'a, Q: ?Sized>(&'a self, right: &Q) -> Option<&'a L>
+    pub fn extend_from_slice(&mut self, pairs: &[(L, R)])
     where
-        R: Borrow<Q>,
-        Q: Hash + Eq,
+        L: Clone,
+        R: Clone,
     {
-        let &BiMap {
-            ref right_data,
-            ref left_data,
-            ref right_hasher,
-            ..
-        } = self;
-        Self::get(right, right_data, left_data, right_hasher)
+        self.extend(pairs.iter().cloned());
     }
 
-    /// Removes a key from the left of the hashmap. Returns the value from the right of the hashmap
-    /// that was associated with this key, if it existed. Will remove both the left and right sides
-    /// of the pair, if it exists, meaning that `get_right` will no longer work for the value
-    /// associated with the key that is removed.
+    /// Removes every pair that was marked for removal in a set of deferred removals. Keys that
+    /// are not in the hashmap (including keys whose pairs were already removed by an earlier key
+    /// in the set) are ignored.
     ///
     /// ```
-    /// # use isomorphism::BiMap;
+    /// # use isomorphism::{BiMap, DeferredRemovals};
     /// let mut map = BiMap::new();
     /// map.insert("Hello", 5);
+    /// map.insert("World", 7);
+    /// map.insert("Hashmaps", 9);
     ///
-    /// assert_eq!(Some(&5), map.get_left("Hello"));
-    /// assert_eq!(Some(&"Hello"), map.get_right(&5));
-    ///
-    /// let old = map.remove_left("Hello");
-    /// assert_eq!(Some(5), old);
+    /// let mut removals = DeferredRemovals::new();
+    /// for (&left, &right) in map.iter() {
+    ///     if right > 5 {
+    ///         removals.remove_left(left);
+    ///     }
+    /// }
     ///
-    /// assert_eq!(None, map.get_left("Hello"));
-    /// assert_eq!(None, map.get_right(&5));
+    /// map.apply_deferred(removals);
+    /// assert_eq!(1, map.len());
+    /// assert_eq!(Some(&5), map.get_left("Hello"));
     /// ```
-    pub fn remove_left<Q: ?Sized>(&mut self, left: &Q) -> Option<R>
-    where
-        L: Borrow<Q>,
-        Q: Hash + Eq,
-    {
-        let &mut BiMap {
-            ref mut len,
-            ref mut left_data,
-            ref mut right_data,
-            ref left_hasher,
-            ref right_hasher,
-        } = self;
-        Self::remove(left, left_data, right_data, left_hasher, right_hasher, len)
-            .map(|(_key, value)| value)
+    pub fn apply_deferred(&mut self, removals: DeferredRemovals<L, R>) {
+        let DeferredRemovals { lefts, rights } = removals;
+
+        for left in lefts {
+            self.remove_left(&left);
+        }
+
+        for right in rights {
+            self.remove_right(&right);
+        }
+
+        self.order.compact();
     }
+}
 
-    /// Removes a key from the right of the hashmap. Returns the value from the left of the hashmap
-    /// that was associated with this key, if it existed. Will remove both the left and right sides
-    /// of the pair, if it exists, meaning that `get_left` will no longer work for the value
-    /// associated with the key that is removed.
+impl<L, V, LH, RH, B> BiMap<L, Arc<V>, LH, RH, B>
+where
+    L: Hash + Eq,
+    V: Hash + Eq,
+    LH: BuildHasher,
+    RH: BuildHasher,
+    B: BitField,
+{
+    /// Gets a key from the left of the hashmap, and returns a clone of the `Arc` on the right of
+    /// the hashmap that associates with it, if it exists. Cloning the `Arc` only increments its
+    /// reference count, and lets the value be held onto after the hashmap is borrowed again.
     ///
     /// ```
     /// # use isomorphism::BiMap;
+    /// # use std::sync::Arc;
     /// let mut map = BiMap::new();
-    /// map.insert("Hello", 5);
-    ///
-    /// assert_eq!(Some(&5), map.get_left("Hello"));
-    /// assert_eq!(Some(&"Hello"), map.get_right(&5));
-    ///
-    /// let old = map.remove_right(&5);
-    /// assert_eq!(Some("Hello"), old);
+    /// map.insert("Hello", Arc::new(5));
     ///
-    /// assert_eq!(None, map.get_left("Hello"));
-    /// assert_eq!(None, map.get_right(&5));
+    /// let value = map.get_left_arc("Hello").unwrap();
+    /// map.remove_left("Hello");
+    /// assert_eq!(5, *value);
     /// ```
-    pub fn remove_right<Q: ?Sized>(&mut self, right: &Q) -> Option<L>
+    pub fn get_left_arc<Q: ?Sized>(&self, left: &Q) -> Option<Arc<V>>
     where
-        R: Borrow<Q>,
+        L: Borrow<Q>,
         Q: Hash + Eq,
     {
-        let &mut BiMap {
-            ref mut len,
-            ref mut left_data,
-            ref mut right_data,
-            ref left_hasher,
-            ref right_hasher,
-        } = self;
-        Self::remove(right, right_data, left_data, right_hasher, left_hasher, len)
-            .map(|(_key, value)| value)
+        self.get_left(left).cloned()
     }
 }
 
@@ -592,8 +3338,8 @@ where
 {
     fn eq(&self, other: &Self) -> bool {
         self.len() == other.len() && self.iter().all(|(left, right)| {
-            other.get_left(left).map_or(false, |r| *right == *r)
-                && other.get_right(right).map_or(false, |l| *left == *l)
+            other.get_left(left).is_some_and(|r| *right == *r)
+                && other.get_right(right).is_some_and(|l| *left == *l)
         })
     }
 }
@@ -608,6 +3354,49 @@ where
 {
 }
 
+impl<L, R, LH, RH, B> PartialOrd for BiMap<L, R, LH, RH, B>
+where
+    L: Hash + Ord,
+    R: Hash + Ord,
+    LH: BuildHasher,
+    RH: BuildHasher,
+    B: BitField,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Bimaps are ordered by their contents, comparing the pairs of each in sorted order
+/// lexicographically. Like equality, this doesn't depend on the order in which the pairs were
+/// inserted or on the layout of the hashmap.
+impl<L, R, LH, RH, B> Ord for BiMap<L, R, LH, RH, B>
+where
+    L: Hash + Ord,
+    R: Hash + Ord,
+    LH: BuildHasher,
+    RH: BuildHasher,
+    B: BitField,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        let mut pairs: Vec<_> = self.iter().collect();
+        let mut other_pairs: Vec<_> = other.iter().collect();
+        pairs.sort_unstable();
+        other_pairs.sort_unstable();
+        pairs.cmp(&other_pairs)
+    }
+}
+
+impl<L, R, LH, RH, B> Hash for BiMap<L, R, LH, RH, B>
+where
+    L: Hash,
+    R: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.content_hash());
+    }
+}
+
 impl<L, R, LH, RH, B> Debug for BiMap<L, R, LH, RH, B>
 where
     L: Debug,
@@ -626,9 +3415,17 @@ impl<'a, L, R, LH, RH, B> IntoIterator for &'a BiMap<L, R, LH, RH, B> {
         let &BiMap {
             ref left_data,
             ref right_data,
+            ref old_left_data,
+            ref old_right_data,
             ..
         } = self;
-        Iter::new(left_data.iter(), right_data)
+        Iter::shrinking(
+            left_data.iter(),
+            right_data,
+            old_left_data.iter(),
+            old_right_data,
+            self.len(),
+        )
     }
 }
 
@@ -640,9 +3437,11 @@ impl<L, R, LH, RH, B> IntoIterator for BiMap<L, R, LH, RH, B> {
         let BiMap {
             left_data,
             right_data,
+            old_left_data,
+            old_right_data,
             ..
         } = self;
-        IntoIter::new(left_data, right_data)
+        IntoIter::shrinking(left_data, right_data, old_left_data, old_right_data)
     }
 }
 
@@ -658,6 +3457,7 @@ where
         let mut output = BiMapBuilder::new()
             .left_hasher(Default::default())
             .right_hasher(Default::default())
+            .reseed_on_collisions()
             .bitfield::<B>()
             .finish();
         output.extend(iter);
@@ -665,6 +3465,45 @@ where
     }
 }
 
+impl<L, R, LH, RH, B> TryFrom<Vec<(L, R)>> for BiMap<L, R, LH, RH, B>
+where
+    L: Hash + Eq,
+    R: Hash + Eq,
+    LH: BuildHasher + Default,
+    RH: BuildHasher + Default,
+    B: BitField,
+{
+    type Error = InjectivityError<L, R>;
+
+    fn try_from(pairs: Vec<(L, R)>) -> Result<Self, Self::Error> {
+        let mut output = BiMapBuilder::new()
+            .left_hasher(Default::default())
+            .right_hasher(Default::default())
+            .reseed_on_collisions()
+            .bitfield::<B>()
+            .capacity(pairs.len())
+            .finish();
+
+        for (index, (left, right)) in pairs.into_iter().enumerate() {
+            if output.contains_left(&left) {
+                return Err(InjectivityError::DuplicateLeft {
+                    index,
+                    pair: (left, right),
+                });
+            }
+            if output.get_right(&right).is_some() {
+                return Err(InjectivityError::DuplicateRight {
+                    index,
+                    pair: (left, right),
+                });
+            }
+            output.insert(left, right);
+        }
+
+        Ok(output)
+    }
+}
+
 impl<L, R, LH, RH, B> Extend<(L, R)> for BiMap<L, R, LH, RH, B>
 where
     L: Hash + Eq,
@@ -674,12 +3513,41 @@ where
     B: BitField,
 {
     fn extend<T: IntoIterator<Item = (L, R)>>(&mut self, iter: T) {
+        let iter = iter.into_iter();
+        let (additional, _) = iter.size_hint();
+        self.grow_for(additional);
+
         for (left, right) in iter {
             self.insert(left, right);
         }
     }
 }
 
+/// Yields the pairs of each chunk of a hashmap being iterated over in parallel.
+#[cfg(feature = "rayon")]
+type ChunkFn<L, R, B> = fn(IntoIter<L, R, B>) -> IntoIter<L, R, B>;
+
+/// Consumes the hashmap, yielding every pair in parallel. The buckets are split between rayon's
+/// threads in the same way as by `into_par_chunks`, so every pair is first moved into a chunk on
+/// the calling thread, before any of the parallel work starts.
+#[cfg(feature = "rayon")]
+impl<L, R, LH, RH, B> IntoParallelIterator for BiMap<L, R, LH, RH, B>
+where
+    L: Send,
+    R: Send,
+    B: BitField + Send,
+{
+    type Item = (L, R);
+    type Iter = FlatMapIter<rayon::vec::IntoIter<IntoIter<L, R, B>>, ChunkFn<L, R, B>>;
+
+    fn into_par_iter(self) -> Self::Iter {
+        let chunks = rayon::current_num_threads();
+        self.into_par_chunks(chunks)
+            .into_par_iter()
+            .flat_map_iter(convert::identity as ChunkFn<L, R, B>)
+    }
+}
+
 #[cfg(feature = "serde")]
 impl<L, R, LH, RH, B> Serialize for BiMap<L, R, LH, RH, B>
 where
@@ -689,7 +3557,7 @@ where
     fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
         use serde::ser::SerializeSeq;
 
-        let mut seq = serializer.serialize_seq(Some(self.len))?;
+        let mut seq = serializer.serialize_seq(Some(self.len()))?;
         for (ref left, ref right) in self.iter() {
             seq.serialize_element(&(left, right))?;
         }
@@ -735,6 +3603,7 @@ where
                 let builder = BiMapBuilder::new()
                     .left_hasher(Default::default())
                     .right_hasher(Default::default())
+                    .reseed_on_collisions()
                     .bitfield::<B>();
                 let mut output = if let Some(size) = map.size_hint() {
                     builder.capacity(size).finish()
@@ -759,7 +3628,131 @@ where
 
 #[cfg(test)]
 mod test {
-    use BiMap;
+    use bitfield::BitField;
+    use {BiMap, BiMapBuilder, ResizePolicy, MAX_LOAD_FACTOR, RESERVE_MARGIN_DIVISOR};
+
+    use common::{IdentityBuildHasher, Multiplicative};
+
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::BuildHasherDefault;
+
+    type IdentityMap = BiMap<u64, u64, IdentityBuildHasher, IdentityBuildHasher>;
+
+    /// Inserts more keys than fit in a neighbourhood, all of which have the same ideal index,
+    /// returning the number of buckets before and after.
+    fn insert_clustered(mut map: IdentityMap) -> (usize, usize) {
+        let buckets = map.left_data.len() as u64;
+        for i in 0..40 {
+            map.insert(i * buckets, i);
+        }
+        map.validate();
+        (buckets as usize, map.left_data.len())
+    }
+
+    #[test]
+    fn default_hashers_reseed() {
+        let map: BiMap<u64, u64> = BiMap::new();
+        assert!(map.reseed.is_some());
+        let map: BiMap<u64, u64> = (0..10).map(|i| (i, i)).collect();
+        assert!(map.reseed.is_some());
+
+        // hashers that the bimap is given don't reseed unless asked to
+        let map: IdentityMap = BiMapBuilder::new()
+            .left_hasher(Default::default())
+            .right_hasher(Default::default())
+            .finish();
+        assert!(map.reseed.is_none());
+    }
+
+    #[test]
+    fn clustered_keys_grow() {
+        let map = BiMapBuilder::new()
+            .capacity(200)
+            .left_hasher(Default::default())
+            .right_hasher(Default::default())
+            .finish();
+        let (before, after) = insert_clustered(map);
+        assert_eq!(before * 2, after);
+    }
+
+    #[test]
+    fn clustered_keys_redistribute() {
+        let mut map: BiMap<u64, u64, Multiplicative, Multiplicative> = BiMapBuilder::new()
+            .capacity(200)
+            .left_hasher(Multiplicative { seed: 1 })
+            .right_hasher(Multiplicative { seed: 1 })
+            .reseed_on_collisions()
+            .resize_policy(ResizePolicy::RedistributeFirst)
+            .finish();
+        // every key has the ideal index 0, but a different hash, until the hashers are replaced
+        let buckets = map.left_data.len() as u64;
+        map.left_hasher = Multiplicative { seed: buckets };
+        map.right_hasher = Multiplicative { seed: buckets };
+        let capacity = map.capacity();
+
+        for i in 0..40 {
+            map.insert(i, i);
+        }
+        map.validate();
+        assert_ne!(buckets, map.left_hasher.seed);
+        assert_eq!(capacity, map.capacity());
+        assert_eq!(0, map.resizes);
+    }
+
+    #[test]
+    fn clustered_keys_redistribute_once() {
+        // without new hashers, moving the pairs again can't break up the cluster, so the hashmap
+        // still grows, but only once
+        let map = BiMapBuilder::new()
+            .capacity(200)
+            .left_hasher(Default::default())
+            .right_hasher(Default::default())
+            .resize_policy(ResizePolicy::RedistributeFirst)
+            .finish();
+        let (before, after) = insert_clustered(map);
+        assert_eq!(before * 2, after);
+    }
+
+    #[test]
+    fn with_capacity_prefaulted_initializes_buckets() {
+        let mut map: BiMap<u64, u64> = BiMap::with_capacity_prefaulted(10_000);
+        let plain: BiMap<u64, u64> = BiMap::with_capacity(10_000);
+        assert_eq!(plain.capacity(), map.capacity());
+        assert_eq!(plain.left_data.len(), map.left_data.len());
+        assert!(map.left_data.iter().all(|bucket| {
+            bucket.data.is_none() && bucket.neighbourhood.empty() && bucket.generation == 0
+        }));
+        assert!(map.right_data.iter().all(|bucket| {
+            bucket.data.is_none() && bucket.neighbourhood.empty() && bucket.generation == 0
+        }));
+
+        for i in 0..1000 {
+            map.insert(i, i);
+        }
+        map.validate();
+        assert_eq!(plain.left_data.len(), map.left_data.len());
+        assert_eq!(Some(&500), map.get_left(&500));
+    }
+
+    #[test]
+    fn extend_resizes_once() {
+        let pairs: Vec<_> = (0..10_000u64).map(|i| (i, i.to_string())).collect();
+
+        let mut extended = BiMap::new();
+        extended.extend(pairs.iter().cloned());
+        assert_eq!(1, extended.resizes);
+
+        let mut from_slice = BiMap::new();
+        from_slice.extend_from_slice(&pairs);
+        assert_eq!(1, from_slice.resizes);
+
+        let mut inserted = BiMap::new();
+        for (left, right) in pairs {
+            inserted.insert(left, right);
+        }
+        assert!(inserted.resizes > 5);
+        assert!(extended == inserted && from_slice == inserted);
+    }
 
     #[test]
     fn test_iteration_empty() {
@@ -767,4 +3760,183 @@ mod test {
         assert_eq!((&map).into_iter().next(), None);
         assert_eq!(map.into_iter().next(), None);
     }
+
+    /// Reserves room for a batch of pairs, inserts exactly that many, and returns whether the
+    /// hashmap had to be resized.
+    fn reserve_then_insert<B: BitField>(keys: &[u64]) -> bool {
+        let mut map = BiMapBuilder::new()
+            .left_hasher(BuildHasherDefault::<DefaultHasher>::default())
+            .right_hasher(BuildHasherDefault::<DefaultHasher>::default())
+            .bitfield::<B>()
+            .finish();
+        map.reserve(keys.len());
+        let resizes = map.resizes;
+        for &key in keys {
+            map.insert(key, key);
+        }
+        map.validate();
+        map.resizes != resizes
+    }
+
+    #[test]
+    fn reserve_never_resizes() {
+        for &count in &[10, 1000, 10000] {
+            let keys: Vec<u64> = (0..count).collect();
+            assert!(!reserve_then_insert::<u32>(&keys));
+            assert!(!reserve_then_insert::<u64>(&keys));
+        }
+    }
+
+    /// Reserves room for a batch of pairs in an empty hashmap, and returns how many buckets it
+    /// was given beyond those needed to stay under the maximum load factor.
+    fn reserve_margin<B: BitField>(additional: usize) -> usize {
+        let mut map: BiMap<u64, u64, _, _, B> = BiMapBuilder::new().bitfield::<B>().finish();
+        let exact = map.buckets_for(additional).unwrap();
+        map.reserve(additional);
+        map.left_data.len() - exact
+    }
+
+    #[test]
+    fn reserve_margin_bounded() {
+        for &additional in &[1000, 100_000] {
+            let exact = (additional as f32 * MAX_LOAD_FACTOR).ceil() as usize;
+            let bound = exact / RESERVE_MARGIN_DIVISOR;
+            assert_eq!(bound + 8, reserve_margin::<u8>(additional));
+            assert_eq!(bound + 16, reserve_margin::<u16>(additional));
+            assert_eq!(bound + 32, reserve_margin::<u32>(additional));
+            assert_eq!(bound + 64, reserve_margin::<u64>(additional));
+        }
+    }
+
+    #[test]
+    fn reserve_clustered_never_resizes() {
+        let mut map: IdentityMap = BiMapBuilder::new()
+            .left_hasher(Default::default())
+            .right_hasher(Default::default())
+            .finish();
+        map.reserve(1000);
+        let buckets = map.left_data.len() as u64;
+        let resizes = map.resizes;
+
+        // groups of half a neighbourhood's worth of keys, each group sharing an ideal index
+        for i in 0..1000 {
+            let (group, member) = (i / 16, i % 16);
+            map.insert(group * 16 + member * buckets, i);
+        }
+        map.validate();
+        assert_eq!(resizes, map.resizes);
+    }
+
+    /// Creates a hashmap holding one pair, whose right bucket has then been emptied, so that the
+    /// left bucket's cross-index dangles. If `left` is set, the left bucket is emptied instead.
+    #[cfg(debug_assertions)]
+    fn dangling(left: bool) -> BiMap<u64, u64> {
+        let mut map = BiMap::new();
+        map.insert(1, 2);
+        let data = if left {
+            &mut map.left_data[..]
+        } else {
+            &mut map.right_data[..]
+        };
+        for bucket in data.iter_mut() {
+            bucket.data = None;
+        }
+        map
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "dangling cross-index")]
+    fn get_left_dangling() {
+        dangling(false).get_left(&1);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "dangling cross-index")]
+    fn get_right_dangling() {
+        dangling(true).get_right(&2);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "dangling cross-index")]
+    fn remove_left_dangling() {
+        dangling(false).remove_left(&1);
+    }
+
+    #[test]
+    fn probes_count_displacement() {
+        let mut map: IdentityMap = BiMapBuilder::new()
+            .left_hasher(Default::default())
+            .right_hasher(Default::default())
+            .finish();
+        let buckets = map.left_data.len() as u64;
+
+        // three keys share ideal bucket 1, and a fourth is alone in bucket 10
+        for &key in &[1, 1 + buckets, 1 + 2 * buckets, 10] {
+            map.insert(key, key);
+        }
+        assert_eq!(Some((&1, 0)), map.get_left_probed(&1));
+        assert_eq!(Some((&(1 + buckets), 1)), map.get_left_probed(&(1 + buckets)));
+        assert_eq!(Some((&(1 + 2 * buckets), 2)), map.get_left_probed(&(1 + 2 * buckets)));
+        assert_eq!(Some((&10, 0)), map.get_left_probed(&10));
+        assert_eq!(None, map.get_left_probed(&(1 + 3 * buckets)));
+    }
+
+    #[test]
+    fn displacement_wraps_around() {
+        let mut map: IdentityMap = BiMapBuilder::new()
+            .left_hasher(Default::default())
+            .right_hasher(Default::default())
+            .finish();
+        let buckets = map.left_data.len() as u64;
+        let last = buckets - 1;
+
+        // the last bucket is taken, and the first 31 buckets hold keys whose ideal bucket is 0
+        map.insert(last, 31);
+        for i in 0..31 {
+            map.insert(i * buckets, i);
+        }
+        let resizes = map.resizes;
+
+        // the nearest free bucket is out of reach for a key whose ideal bucket is the last one, so
+        // one of the keys from bucket 0 has to be moved out of the way, across the wrap around
+        map.insert(last + buckets, 32);
+        map.validate();
+        assert_eq!(resizes, map.resizes);
+        assert_eq!(Some(&32), map.get_left(&(last + buckets)));
+    }
+
+    #[test]
+    fn probes_wrap_around() {
+        let mut map: IdentityMap = BiMapBuilder::new()
+            .left_hasher(Default::default())
+            .right_hasher(Default::default())
+            .finish();
+        let buckets = map.left_data.len() as u64;
+
+        // the neighbourhood of the last bucket carries on from the first
+        let last = buckets - 1;
+        map.insert(last, 0);
+        map.insert(last + buckets, 1);
+        assert_eq!(Some((&0, 0)), map.get_left_probed(&last));
+        assert_eq!(Some((&1, 1)), map.get_left_probed(&(last + buckets)));
+    }
+
+    #[test]
+    fn clear_keeps_buckets() {
+        let mut map: BiMap<u64, u64> = (0..100).map(|i| (i, i)).collect();
+        let left = map.left_data.as_ptr();
+        let right = map.right_data.as_ptr();
+
+        map.clear();
+        assert_eq!(left, map.left_data.as_ptr());
+        assert_eq!(right, map.right_data.as_ptr());
+        map.validate();
+
+        map.extend((0..100).map(|i| (i, i + 1)));
+        assert_eq!(Some(&1), map.get_left(&0));
+        map.validate();
+    }
 }
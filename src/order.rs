@@ -0,0 +1,267 @@
+//! Tracking of the order in which pairs were inserted into a bimap.
+use bucket::Bucket;
+
+use std::mem;
+
+/// Marks the lack of a node, or of a bucket.
+const NONE: usize = usize::MAX;
+
+/// A single pair within the insertion order, as part of a doubly linked list.
+#[derive(Clone, Copy, Debug)]
+struct Node {
+    /// The node of the pair inserted just before this one.
+    prev: usize,
+    /// The node of the pair inserted just after this one.
+    next: usize,
+    /// The index of the pair's left key within the left_data array.
+    bucket: usize,
+}
+
+/// The order in which the pairs of a bimap were inserted, if it is being tracked at all. Pairs
+/// are linked together through nodes that keep the same index for as long as the pair is in the
+/// bimap, so moving a left key to another bucket only has to update the node that points to it.
+#[derive(Debug)]
+pub(crate) struct Order {
+    /// Whether the order is being tracked. If not, every other field stays empty.
+    enabled: bool,
+    /// For each bucket in the left_data array, the node of the pair whose left key is in it.
+    nodes_by_bucket: Box<[usize]>,
+    /// The nodes of the pairs, indexed by node. Nodes that aren't in use are kept in the free
+    /// list, linked through their next fields.
+    nodes: Vec<Node>,
+    /// The node of the pair that was inserted first.
+    head: usize,
+    /// The node of the pair that was inserted last.
+    tail: usize,
+    /// The first unused node.
+    free: usize,
+    /// The nodes of left keys that have been taken out of their buckets while they are moved to
+    /// another bucket.
+    moving: Vec<usize>,
+}
+
+impl Order {
+    /// An order that is not being tracked.
+    pub fn disabled() -> Self {
+        Order {
+            enabled: false,
+            nodes_by_bucket: Vec::new().into(),
+            nodes: Vec::new(),
+            head: NONE,
+            tail: NONE,
+            free: NONE,
+            moving: Vec::new(),
+        }
+    }
+
+    /// An empty order that is being tracked, for a left_data array with a given number of
+    /// buckets.
+    pub fn enabled(buckets: usize) -> Self {
+        Order {
+            enabled: true,
+            nodes_by_bucket: vec![NONE; buckets].into(),
+            ..Order::disabled()
+        }
+    }
+
+    /// Is the order being tracked?
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Replaces this order with an empty one for a given number of buckets, which is tracked if
+    /// this one was. Returns the old order.
+    pub fn reset(&mut self, buckets: usize) -> Self {
+        let empty = if self.enabled {
+            Order::enabled(buckets)
+        } else {
+            Order::disabled()
+        };
+        mem::replace(self, empty)
+    }
+
+    /// Returns how many bytes of heap memory the order is using.
+    pub fn memory_usage(&self) -> usize {
+        mem::size_of_val(&*self.nodes_by_bucket)
+            + self.nodes.capacity() * mem::size_of::<Node>()
+            + self.moving.capacity() * mem::size_of::<usize>()
+    }
+
+    /// Adds the pair whose left key is in a given bucket as the last pair in the order.
+    pub fn push_back(&mut self, bucket: usize) {
+        if !self.enabled {
+            return;
+        }
+
+        let node = Node {
+            prev: self.tail,
+            next: NONE,
+            bucket,
+        };
+        let index = if self.free == NONE {
+            self.nodes.push(node);
+            self.nodes.len() - 1
+        } else {
+            let index = self.free;
+            self.free = self.nodes[index].next;
+            self.nodes[index] = node;
+            index
+        };
+
+        if self.tail == NONE {
+            self.head = index;
+        } else {
+            self.nodes[self.tail].next = index;
+        }
+        self.tail = index;
+        self.nodes_by_bucket[bucket] = index;
+    }
+
+    /// Removes the pair whose left key is in a given bucket from the order, if it is in it.
+    pub fn remove(&mut self, bucket: usize) {
+        if !self.enabled {
+            return;
+        }
+
+        let index = mem::replace(&mut self.nodes_by_bucket[bucket], NONE);
+        if index == NONE {
+            return;
+        }
+
+        let Node { prev, next, .. } = self.nodes[index];
+        if prev == NONE {
+            self.head = next;
+        } else {
+            self.nodes[prev].next = next;
+        }
+        if next == NONE {
+            self.tail = prev;
+        } else {
+            self.nodes[next].prev = prev;
+        }
+
+        self.nodes[index] = Node {
+            prev: NONE,
+            next: self.free,
+            bucket: NONE,
+        };
+        self.free = index;
+    }
+
+    /// Rebuilds the order without any unused nodes, keeping the same pairs in the same order. This
+    /// frees the memory of the nodes of pairs that have been removed, and is intended to be used
+    /// after removing many pairs at once.
+    pub fn compact(&mut self) {
+        if self.enabled && self.free != NONE {
+            let old = self.reset(self.nodes_by_bucket.len());
+            for bucket in old.buckets() {
+                self.push_back(bucket);
+            }
+        }
+    }
+
+    /// Notes that the left key in a given bucket is being taken out of it, to be moved to another
+    /// bucket. Each call must be matched by a later call to `place`, in reverse order.
+    pub fn lift(&mut self, bucket: usize) {
+        if self.enabled {
+            let index = mem::replace(&mut self.nodes_by_bucket[bucket], NONE);
+            self.moving.push(index);
+        }
+    }
+
+    /// Notes that the left key most recently given to `lift` has been put into a given bucket.
+    pub fn place(&mut self, bucket: usize) {
+        if self.enabled {
+            let index = self.moving.pop().unwrap();
+            self.nodes_by_bucket[bucket] = index;
+            if index != NONE {
+                self.nodes[index].bucket = bucket;
+            }
+        }
+    }
+
+    /// Returns the bucket holding the left key of the pair that was inserted first.
+    pub fn first(&self) -> Option<usize> {
+        if self.head == NONE {
+            None
+        } else {
+            Some(self.nodes[self.head].bucket)
+        }
+    }
+
+    /// Returns the buckets holding the left keys of every pair, in the order they were inserted.
+    pub fn buckets(&self) -> Buckets<'_> {
+        Buckets {
+            order: self,
+            next: self.head,
+        }
+    }
+
+    /// Takes every pair out of a set of buckets, in insertion order.
+    pub fn take_pairs<L, R, B>(
+        &self,
+        left_data: &mut [Bucket<L, usize, B>],
+        right_data: &mut [Bucket<R, usize, B>],
+    ) -> Vec<(L, R)> {
+        self.buckets()
+            .map(|left_index| {
+                let (left, right_index, _) = left_data[left_index].data.take().unwrap();
+                let (right, ..) = right_data[right_index].data.take().unwrap();
+                (left, right)
+            })
+            .collect()
+    }
+
+    /// Checks that the order holds exactly the buckets with a pair in them, panicking if it
+    /// doesn't.
+    pub fn validate<K, B>(&self, left_data: &[Bucket<K, usize, B>]) {
+        if !self.enabled {
+            return;
+        }
+
+        assert_eq!(left_data.len(), self.nodes_by_bucket.len(), "order has the wrong length");
+        assert!(self.moving.is_empty(), "order has left keys that were never placed");
+        let mut count = 0;
+        for bucket in self.buckets() {
+            count += 1;
+            assert!(left_data[bucket].pair().is_some(), "order holds unpaired bucket {}", bucket);
+            assert_eq!(
+                self.nodes[self.nodes_by_bucket[bucket]].bucket,
+                bucket,
+                "order holds bucket {} through the wrong node",
+                bucket
+            );
+        }
+
+        for (bucket, &node) in self.nodes_by_bucket.iter().enumerate() {
+            assert!(
+                node == NONE || left_data[bucket].pair().is_some(),
+                "unpaired bucket {} still has a node",
+                bucket
+            );
+        }
+
+        let paired = left_data.iter().filter(|bucket| bucket.pair().is_some()).count();
+        assert_eq!(paired, count, "order is missing pairs");
+    }
+}
+
+/// An iterator over the buckets of an order, from the first inserted pair to the last.
+pub(crate) struct Buckets<'a> {
+    order: &'a Order,
+    next: usize,
+}
+
+impl<'a> Iterator for Buckets<'a> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.next == NONE {
+            None
+        } else {
+            let node = self.order.nodes[self.next];
+            self.next = node.next;
+            Some(node.bucket)
+        }
+    }
+}
@@ -0,0 +1,12 @@
+//! Control over how far `BiMap::retain_until` goes.
+
+/// What `BiMap::retain_until` should do with the pair it has just visited.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RetainControl {
+    /// Keep the pair, and carry on to the next one.
+    Keep,
+    /// Remove the pair, and carry on to the next one.
+    Remove,
+    /// Keep the pair, and stop without visiting any more pairs, which are all kept.
+    Stop,
+}
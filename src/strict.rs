@@ -0,0 +1,179 @@
+//! A bimap that rejects inserts which would overwrite existing pairs, and a name for the bimap
+//! that doesn't.
+use BiMap;
+use bitfield::{BitField, DefaultBitField};
+
+use std::borrow::Borrow;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hash};
+use std::ops::Deref;
+
+/// A `BiMap` whose inserts never overwrite existing pairs. Where `BiMap::insert` removes any pair
+/// that shares a key with the inserted pair, `StrictBiMap::insert` leaves the hashmap unmodified
+/// and gives the inserted pair back, in the same way as `BiMap::try_insert`. This makes the
+/// choice between the two behaviours part of the type, so that the wrong one can't be used by
+/// accident.
+///
+/// Every method of `BiMap` that doesn't modify the hashmap can be used through `Deref`.
+///
+/// ```
+/// # use isomorphism::StrictBiMap;
+/// let mut map = StrictBiMap::new();
+/// assert_eq!(Ok(()), map.insert("Hello", 5));
+/// assert_eq!(Err(("Hello", 7)), map.insert("Hello", 7));
+///
+/// // lookups go through to the underlying bimap
+/// assert_eq!(Some(&5), map.get_left("Hello"));
+/// assert_eq!(1, map.len());
+/// ```
+#[derive(Debug)]
+pub struct StrictBiMap<
+    L,
+    R,
+    LH = RandomState,
+    RH = RandomState,
+    B = DefaultBitField,
+> {
+    inner: BiMap<L, R, LH, RH, B>,
+}
+
+/// A `BiMap` whose inserts overwrite existing pairs, which is the behaviour of every `BiMap`. This
+/// is the same type as `BiMap`, for code that uses it alongside `StrictBiMap` and wants the choice
+/// between the two behaviours spelled out in its types.
+///
+/// ```
+/// # use isomorphism::{OverwriteBiMap, StrictBiMap};
+/// let mut overwriting: OverwriteBiMap<&str, u64> = OverwriteBiMap::new();
+/// overwriting.insert("Hello", 5);
+/// assert_eq!((Some(5), None), overwriting.insert("Hello", 7));
+///
+/// let mut strict: StrictBiMap<&str, u64> = StrictBiMap::new();
+/// strict.insert("Hello", 5).unwrap();
+/// assert_eq!(Err(("Hello", 7)), strict.insert("Hello", 7));
+/// ```
+pub type OverwriteBiMap<L, R, LH = RandomState, RH = RandomState, B = DefaultBitField> =
+    BiMap<L, R, LH, RH, B>;
+
+impl<L, R> Default for StrictBiMap<L, R> {
+    fn default() -> Self {
+        StrictBiMap {
+            inner: BiMap::new(),
+        }
+    }
+}
+
+impl<L, R> StrictBiMap<L, R> {
+    /// Creates a new empty StrictBiMap.
+    ///
+    /// ```
+    /// # use isomorphism::StrictBiMap;
+    /// let map: StrictBiMap<u64, char> = StrictBiMap::new();
+    /// ```
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+impl<L, R, LH, RH, B> StrictBiMap<L, R, LH, RH, B> {
+    /// Returns the underlying bimap, which no longer rejects overwriting inserts.
+    ///
+    /// ```
+    /// # use isomorphism::StrictBiMap;
+    /// let mut map = StrictBiMap::new();
+    /// map.insert("Hello", 5).unwrap();
+    ///
+    /// let mut map = map.into_inner();
+    /// assert_eq!((Some(5), None), map.insert("Hello", 7));
+    /// ```
+    pub fn into_inner(self) -> BiMap<L, R, LH, RH, B> {
+        self.inner
+    }
+}
+
+impl<L, R, LH, RH, B> StrictBiMap<L, R, LH, RH, B>
+where
+    L: Hash + Eq,
+    R: Hash + Eq,
+    LH: BuildHasher,
+    RH: BuildHasher,
+    B: BitField,
+{
+    /// Inserts an (L, R) pair into the hashmap, unless either key is already in it, in which case
+    /// the hashmap is left unmodified and the pair is given back as an error.
+    ///
+    /// ```
+    /// # use isomorphism::StrictBiMap;
+    /// let mut map = StrictBiMap::new();
+    /// assert_eq!(Ok(()), map.insert("Hello", 5));
+    /// assert_eq!(Err(("World", 5)), map.insert("World", 5));
+    /// ```
+    pub fn insert(&mut self, left: L, right: R) -> Result<(), (L, R)> {
+        self.inner.try_insert(left, right)
+    }
+
+    /// Removes a key from the left of the hashmap, returning the right value that was associated
+    /// with it, if it existed. See `BiMap::remove_left`.
+    ///
+    /// ```
+    /// # use isomorphism::StrictBiMap;
+    /// let mut map = StrictBiMap::new();
+    /// map.insert("Hello", 5).unwrap();
+    ///
+    /// assert_eq!(Some(5), map.remove_left("Hello"));
+    /// assert_eq!(Ok(()), map.insert("World", 5));
+    /// ```
+    pub fn remove_left<Q: ?Sized>(&mut self, left: &Q) -> Option<R>
+    where
+        L: Borrow<Q>,
+        Q: Hash + Eq,
+    {
+        self.inner.remove_left(left)
+    }
+
+    /// Removes a key from the right of the hashmap, returning the left value that was associated
+    /// with it, if it existed. See `BiMap::remove_right`.
+    ///
+    /// ```
+    /// # use isomorphism::StrictBiMap;
+    /// let mut map = StrictBiMap::new();
+    /// map.insert("Hello", 5).unwrap();
+    ///
+    /// assert_eq!(Some("Hello"), map.remove_right(&5));
+    /// assert_eq!(Ok(()), map.insert("Hello", 7));
+    /// ```
+    pub fn remove_right<Q: ?Sized>(&mut self, right: &Q) -> Option<L>
+    where
+        R: Borrow<Q>,
+        Q: Hash + Eq,
+    {
+        self.inner.remove_right(right)
+    }
+
+    /// Removes every pair from the hashmap. See `BiMap::clear`.
+    ///
+    /// ```
+    /// # use isomorphism::StrictBiMap;
+    /// let mut map = StrictBiMap::new();
+    /// map.insert("Hello", 5).unwrap();
+    ///
+    /// map.clear();
+    /// assert!(map.is_empty());
+    /// ```
+    pub fn clear(&mut self) {
+        self.inner.clear();
+    }
+}
+
+impl<L, R, LH, RH, B> From<BiMap<L, R, LH, RH, B>> for StrictBiMap<L, R, LH, RH, B> {
+    fn from(inner: BiMap<L, R, LH, RH, B>) -> Self {
+        StrictBiMap { inner }
+    }
+}
+
+impl<L, R, LH, RH, B> Deref for StrictBiMap<L, R, LH, RH, B> {
+    type Target = BiMap<L, R, LH, RH, B>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
@@ -0,0 +1,226 @@
+//! A bimap that resolves conflicting pairs by their timestamps, for merging replicas.
+use BiMap;
+use bitfield::{BitField, DefaultBitField};
+
+use std::borrow::Borrow;
+use std::collections::hash_map::RandomState;
+use std::cmp;
+use std::hash::{BuildHasher, Hash, Hasher};
+
+/// A left key along with the timestamp of the pair it belongs to. Hashing and equality only look
+/// at the key, so that pairs can be looked up by the key alone.
+#[derive(Debug)]
+struct Stamped<L> {
+    key: L,
+    stamp: u64,
+}
+
+impl<L: PartialEq> PartialEq for Stamped<L> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl<L: Eq> Eq for Stamped<L> {}
+
+impl<L: Hash> Hash for Stamped<L> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key.hash(state);
+    }
+}
+
+impl<L> Borrow<L> for Stamped<L> {
+    fn borrow(&self) -> &L {
+        &self.key
+    }
+}
+
+/// A `BiMap` in which every pair carries a logical timestamp, and a pair is only inserted if it is
+/// newer than every pair it conflicts with. This gives last-write-wins merging of updates from
+/// several replicas: whatever order the updates arrive in, a pair that is newer than all of the
+/// pairs it conflicts with ends up in the hashmap, and older conflicting pairs don't.
+///
+/// Pairs with equal timestamps don't replace each other, so whichever arrives first is kept. For
+/// merges to be deterministic every update needs a unique timestamp, such as a Lamport clock
+/// combined with a replica identifier. Pairs that were removed to make room for a newer pair are
+/// not restored if that pair is itself replaced later, so replicas that receive updates which
+/// conflict in chains can still differ.
+///
+/// ```
+/// # use isomorphism::TimestampedBiMap;
+/// let mut map = TimestampedBiMap::new();
+/// map.merge_timestamped(vec![("Hello", 5, 2), ("Hello", 7, 1)]);
+///
+/// // the older pairing arrived later, but lost
+/// assert_eq!(Some(&5), map.get_left(&"Hello"));
+/// assert_eq!(Some(2), map.timestamp_left(&"Hello"));
+/// ```
+#[derive(Debug)]
+pub struct TimestampedBiMap<
+    L,
+    R,
+    LH = RandomState,
+    RH = RandomState,
+    B = DefaultBitField,
+> {
+    inner: BiMap<Stamped<L>, R, LH, RH, B>,
+}
+
+impl<L, R> Default for TimestampedBiMap<L, R> {
+    fn default() -> Self {
+        TimestampedBiMap {
+            inner: BiMap::new(),
+        }
+    }
+}
+
+impl<L, R> TimestampedBiMap<L, R> {
+    /// Creates a new empty TimestampedBiMap.
+    ///
+    /// ```
+    /// # use isomorphism::TimestampedBiMap;
+    /// let map: TimestampedBiMap<u64, char> = TimestampedBiMap::new();
+    /// ```
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+impl<L, R, LH, RH, B> TimestampedBiMap<L, R, LH, RH, B> {
+    /// Returns the number of pairs in the hashmap.
+    ///
+    /// ```
+    /// # use isomorphism::TimestampedBiMap;
+    /// let mut map = TimestampedBiMap::new();
+    /// map.insert_timestamped("Hello", 5, 1);
+    /// assert_eq!(1, map.len());
+    /// ```
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns true if the hashmap holds no pairs.
+    ///
+    /// ```
+    /// # use isomorphism::TimestampedBiMap;
+    /// let map: TimestampedBiMap<&str, u64> = TimestampedBiMap::new();
+    /// assert!(map.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// An iterator visiting all pairs in an arbitrary order, along with their timestamps.
+    ///
+    /// ```
+    /// # use isomorphism::TimestampedBiMap;
+    /// let mut map = TimestampedBiMap::new();
+    /// map.insert_timestamped("Hello", 5, 3);
+    ///
+    /// let pairs: Vec<_> = map.iter().collect();
+    /// assert_eq!(vec![(&"Hello", &5, 3)], pairs);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = (&L, &R, u64)> {
+        self.inner.iter().map(|(left, right)| (&left.key, right, left.stamp))
+    }
+}
+
+impl<L, R, LH, RH, B> TimestampedBiMap<L, R, LH, RH, B>
+where
+    L: Hash + Eq,
+    R: Hash + Eq,
+    LH: BuildHasher,
+    RH: BuildHasher,
+    B: BitField,
+{
+    /// Inserts an (L, R) pair with a given timestamp, if that timestamp is newer than those of the
+    /// pairs already holding the L or the R, in which case those pairs are removed. Returns
+    /// whether the pair was inserted.
+    ///
+    /// ```
+    /// # use isomorphism::TimestampedBiMap;
+    /// let mut map = TimestampedBiMap::new();
+    /// assert!(map.insert_timestamped("Hello", 5, 2));
+    /// assert!(!map.insert_timestamped("World", 5, 1));
+    /// assert!(map.insert_timestamped("World", 5, 3));
+    ///
+    /// assert_eq!(None, map.get_left(&"Hello"));
+    /// assert_eq!(Some(&"World"), map.get_right(&5));
+    /// ```
+    pub fn insert_timestamped(&mut self, left: L, right: R, stamp: u64) -> bool {
+        // None is less than any timestamp, so a pair without conflicts is always inserted
+        let newest = cmp::max(self.timestamp_left(&left), self.timestamp_right(&right));
+        if Some(stamp) > newest {
+            self.inner.insert(Stamped { key: left, stamp }, right);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Merges timestamped pairs into the hashmap, as if each one was given to
+    /// `insert_timestamped` in turn.
+    ///
+    /// ```
+    /// # use isomorphism::TimestampedBiMap;
+    /// let mut map = TimestampedBiMap::new();
+    /// map.merge_timestamped(vec![("Hello", 5, 1), ("World", 7, 1)]);
+    /// map.merge_timestamped(vec![("Hello", 7, 2)]);
+    ///
+    /// assert_eq!(1, map.len());
+    /// assert_eq!(Some(&7), map.get_left(&"Hello"));
+    /// ```
+    pub fn merge_timestamped<I: IntoIterator<Item = (L, R, u64)>>(&mut self, iter: I) {
+        for (left, right, stamp) in iter {
+            self.insert_timestamped(left, right, stamp);
+        }
+    }
+
+    /// Gets the right value paired with a left key, if there is one.
+    ///
+    /// ```
+    /// # use isomorphism::TimestampedBiMap;
+    /// let mut map = TimestampedBiMap::new();
+    /// map.insert_timestamped("Hello", 5, 1);
+    /// assert_eq!(Some(&5), map.get_left(&"Hello"));
+    /// ```
+    pub fn get_left(&self, left: &L) -> Option<&R> {
+        self.inner.get_left(left)
+    }
+
+    /// Gets the left value paired with a right key, if there is one.
+    ///
+    /// ```
+    /// # use isomorphism::TimestampedBiMap;
+    /// let mut map = TimestampedBiMap::new();
+    /// map.insert_timestamped("Hello", 5, 1);
+    /// assert_eq!(Some(&"Hello"), map.get_right(&5));
+    /// ```
+    pub fn get_right(&self, right: &R) -> Option<&L> {
+        self.inner.get_right(right).map(|left| &left.key)
+    }
+
+    /// Gets the timestamp of the pair holding a left key, if there is one.
+    ///
+    /// ```
+    /// # use isomorphism::TimestampedBiMap;
+    /// let mut map = TimestampedBiMap::new();
+    /// map.insert_timestamped("Hello", 5, 4);
+    /// assert_eq!(Some(4), map.timestamp_left(&"Hello"));
+    /// ```
+    pub fn timestamp_left(&self, left: &L) -> Option<u64> {
+        self.inner.get_left(left).and_then(|right| self.timestamp_right(right))
+    }
+
+    /// Gets the timestamp of the pair holding a right key, if there is one.
+    ///
+    /// ```
+    /// # use isomorphism::TimestampedBiMap;
+    /// let mut map = TimestampedBiMap::new();
+    /// map.insert_timestamped("Hello", 5, 4);
+    /// assert_eq!(Some(4), map.timestamp_right(&5));
+    /// ```
+    pub fn timestamp_right(&self, right: &R) -> Option<u64> {
+        self.inner.get_right(right).map(|left| left.stamp)
+    }
+}
@@ -0,0 +1,48 @@
+//! Runs the benchmark workloads on a handful of pairs, so that a broken benchmark or baseline is
+//! caught by `cargo test` rather than when the benchmarks are next run.
+extern crate isomorphism;
+
+#[path = "../benches/common/mod.rs"]
+mod common;
+
+use common::{Pairs, TwoMaps};
+use isomorphism::BiMap;
+
+fn run<M: Pairs>() {
+    let pairs = common::pairs(100);
+    let mut map: M = common::insert_all(&pairs);
+    assert_eq!(100, map.len());
+    assert_eq!(100, common::get_left_all(&map, &pairs));
+    assert_eq!(100, common::get_right_all(&map, &pairs));
+    assert_eq!(100, common::remove_left_all(&mut map, &pairs));
+    assert_eq!(0, map.len());
+}
+
+#[test]
+fn workloads_bimap() {
+    run::<BiMap<u64, u64>>();
+}
+
+#[test]
+fn workloads_two_maps() {
+    run::<TwoMaps>();
+}
+
+#[test]
+fn two_maps_overwrites_like_bimap() {
+    let ops = [(1, 10), (2, 20), (1, 20), (3, 30), (3, 10), (2, 30)];
+
+    let mut bimap: BiMap<u64, u64> = Pairs::new();
+    let mut two_maps = TwoMaps::new();
+    for &(left, right) in ops.iter() {
+        Pairs::insert(&mut bimap, left, right);
+        two_maps.insert(left, right);
+        assert_eq!(Pairs::len(&bimap), two_maps.len());
+        for key in 0..4 {
+            assert_eq!(Pairs::get_left(&bimap, &key), two_maps.get_left(&key));
+        }
+        for key in 0..4 {
+            assert_eq!(Pairs::get_right(&bimap, &(key * 10)), two_maps.get_right(&(key * 10)));
+        }
+    }
+}
@@ -0,0 +1,88 @@
+//! The hashers shared by the tests, which let a test control which bucket each key belongs in.
+//! This is compiled into the `main` test, and into the unit tests of the crate through a `path`
+//! attribute, so it only depends on the standard library.
+use std::hash::{BuildHasher, BuildHasherDefault, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// A hasher that uses integer keys as their own hash values, so that tests can control which
+/// bucket each key belongs in.
+#[derive(Default)]
+pub struct IdentityHasher(pub u64);
+
+impl Hasher for IdentityHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 << 8) | u64::from(byte);
+        }
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.0 = value;
+    }
+}
+
+pub type IdentityBuildHasher = BuildHasherDefault<IdentityHasher>;
+
+/// A hasher that gives every key the same hash, as the worst possible hasher would.
+#[derive(Default)]
+pub struct ConstantHasher;
+
+impl Hasher for ConstantHasher {
+    fn finish(&self) -> u64 {
+        0
+    }
+
+    fn write(&mut self, _bytes: &[u8]) {}
+}
+
+pub type ConstantBuildHasher = BuildHasherDefault<ConstantHasher>;
+
+/// A multiplicative hasher, seeded from a counter whenever one is created with `Default`. The seed
+/// 0 is as bad as a seed can be, as it gives every key the same hash. Seeds from `Default` are
+/// odd.
+pub struct Multiplicative {
+    pub seed: u64,
+}
+
+impl Default for Multiplicative {
+    fn default() -> Self {
+        static SEEDS: AtomicU64 = AtomicU64::new(1);
+        Multiplicative {
+            seed: SEEDS.fetch_add(2, Ordering::Relaxed),
+        }
+    }
+}
+
+impl BuildHasher for Multiplicative {
+    type Hasher = MultiplicativeHasher;
+
+    fn build_hasher(&self) -> MultiplicativeHasher {
+        MultiplicativeHasher {
+            seed: self.seed,
+            inner: IdentityHasher(0),
+        }
+    }
+}
+
+pub struct MultiplicativeHasher {
+    seed: u64,
+    inner: IdentityHasher,
+}
+
+impl Hasher for MultiplicativeHasher {
+    fn finish(&self) -> u64 {
+        self.inner.finish().wrapping_mul(self.seed)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        self.inner.write(bytes);
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.inner.write_u64(value);
+    }
+}
@@ -1,13 +1,32 @@
 extern crate isomorphism;
 #[macro_use]
 extern crate quickcheck;
+#[cfg(feature = "rayon")]
+extern crate rayon;
 
-use std::collections::HashSet;
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::collections::hash_map::RandomState;
+use std::collections::{BTreeSet, HashSet};
+use std::convert::TryFrom;
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::num::NonZeroUsize;
+use std::ptr;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+use std::thread;
 
-use isomorphism::{BiMap, BiMapBuilder};
+use isomorphism::{
+    BiMap, BiMapBuilder, DeferredRemovals, ExportError, InjectivityError, KeyedBiMap,
+    OverwriteBiMap, ResolveError, RetainControl, StrictBiMap, TimestampedBiMap, TryReserveError,
+};
 
 use quickcheck::TestResult;
 
+mod common;
+
+use common::{ConstantBuildHasher, IdentityBuildHasher, Multiplicative};
+
 quickcheck! {
     fn test_capacity(cap: usize) -> bool {
         BiMapBuilder::new().capacity(cap).finish::<(), ()>().capacity() >= cap
@@ -17,7 +36,7 @@ quickcheck! {
 quickcheck! {
     fn remove_from_empty(a: usize, b: char) -> bool {
         let mut map: BiMap<usize, char> = BiMap::new();
-        map.remove_left(&a) == None && map.remove_right(&b) == None
+        map.remove_left(&a).is_none() && map.remove_right(&b).is_none()
     }
 }
 
@@ -83,8 +102,8 @@ quickcheck! {
         inputs
             .into_iter()
             .all(|(a, b)| {
-                let old_b = map.get_left(&a).map(|&x| x);
-                let old_a = map.get_right(&b).map(|&x| x);
+                let old_b = map.get_left(&a).copied();
+                let old_a = map.get_right(&b).copied();
 
                 map.insert(a, b) == (old_b, old_a)
             })
@@ -130,3 +149,1994 @@ quickcheck! {
         refs == vals
     }
 }
+
+quickcheck! {
+    fn insert_if_changed_unchanged(inputs: Vec<(usize, char)>, a: usize, b: char) -> bool {
+        let mut map = BiMap::new();
+
+        for (a, b) in inputs {
+            map.insert(a, b);
+        }
+        map.insert(a, b);
+
+        let mut before: Vec<_> = map.iter().map(|(&a, &b)| (a, b)).collect();
+        let unchanged = !map.insert_if_changed(a, b);
+        let mut after: Vec<_> = map.iter().map(|(&a, &b)| (a, b)).collect();
+
+        before.sort();
+        after.sort();
+
+        unchanged && before == after
+    }
+}
+
+quickcheck! {
+    fn insert_if_changed_changed(inputs: Vec<(usize, char)>, a: usize, b: char) -> TestResult {
+        let mut map = BiMap::new();
+
+        for (a, b) in inputs {
+            map.insert(a, b);
+        }
+
+        if map.get_left(&a) == Some(&b) {
+            return TestResult::discard();
+        }
+
+        let changed = map.insert_if_changed(a, b);
+
+        TestResult::from_bool(
+            changed && map.get_left(&a) == Some(&b) && map.get_right(&b) == Some(&a),
+        )
+    }
+}
+
+quickcheck! {
+    fn get_left_zip(inputs: Vec<(usize, char)>, queries: Vec<usize>) -> bool {
+        let mut map = BiMap::new();
+
+        for (a, b) in inputs {
+            map.insert(a, b);
+        }
+
+        let results: Vec<_> = map.get_left_zip(&queries).collect();
+
+        results.len() == queries.len()
+            && results
+                .iter()
+                .zip(queries.iter())
+                .all(|(&(key, value), query)| key == query && value == map.get_left(query))
+    }
+}
+
+#[test]
+fn get_left_zip_hits_and_misses() {
+    let mut map = BiMap::new();
+    map.insert(1, 'a');
+    map.insert(2, 'b');
+    map.insert(3, 'c');
+
+    let queries = [3, 4, 1, 1, 5, 2];
+    let results: Vec<_> = map.get_left_zip(&queries).collect();
+
+    assert_eq!(
+        vec![
+            (&3, Some(&'c')),
+            (&4, None),
+            (&1, Some(&'a')),
+            (&1, Some(&'a')),
+            (&5, None),
+            (&2, Some(&'b')),
+        ],
+        results
+    );
+}
+
+fn prefix_map() -> BiMap<String, usize> {
+    ["apple", "apricot", "banana", "blueberry", "cherry"]
+        .iter()
+        .enumerate()
+        .map(|(i, &fruit)| (fruit.to_owned(), i))
+        .collect()
+}
+
+#[test]
+fn left_with_prefix_matching() {
+    let map = prefix_map();
+
+    let mut found: Vec<_> = map.left_with_prefix("ap").map(|(left, _)| left.clone()).collect();
+    found.sort();
+
+    assert_eq!(vec!["apple".to_owned(), "apricot".to_owned()], found);
+}
+
+#[test]
+fn left_with_prefix_empty() {
+    let map = prefix_map();
+    assert_eq!(map.len(), map.left_with_prefix("").count());
+}
+
+#[test]
+fn left_with_prefix_none() {
+    let map = prefix_map();
+    assert_eq!(0, map.left_with_prefix("durian").count());
+}
+
+#[test]
+fn try_reserve_exact_small() {
+    let mut map = BiMap::new();
+    map.insert(0, 0);
+
+    assert_eq!(Ok(()), map.try_reserve_exact(100));
+    assert!(map.capacity() >= 100);
+    assert_eq!(Some(&0), map.get_left(&0));
+
+    for i in 1..101 {
+        map.insert(i, i * 2);
+    }
+    assert_eq!(101, map.len());
+    assert!((0..101).all(|i| map.get_left(&i) == Some(&(i * 2))));
+}
+
+#[test]
+fn try_reserve_exact_overflow() {
+    let mut map: BiMap<usize, char> = BiMap::new();
+    assert_eq!(
+        Err(TryReserveError::CapacityOverflow),
+        map.try_reserve_exact(usize::MAX)
+    );
+
+    map.insert(0, 'a');
+    assert_eq!(
+        Err(TryReserveError::CapacityOverflow),
+        map.try_reserve_exact(usize::MAX)
+    );
+    assert_eq!(Some(&'a'), map.get_left(&0));
+}
+
+#[test]
+fn into_par_chunks_threads() {
+    let map: BiMap<usize, usize> = (0..100).map(|i| (i, i + 1000)).collect();
+
+    let chunks = map.into_par_chunks(4);
+    assert_eq!(4, chunks.len());
+
+    let handles: Vec<_> = chunks
+        .into_iter()
+        .map(|chunk| thread::spawn(move || chunk.collect::<Vec<_>>()))
+        .collect();
+
+    let mut pairs = Vec::new();
+    for handle in handles {
+        let chunk = handle.join().unwrap();
+        assert_eq!(25, chunk.len());
+        pairs.extend(chunk);
+    }
+
+    pairs.sort();
+    assert_eq!((0..100).map(|i| (i, i + 1000)).collect::<Vec<_>>(), pairs);
+}
+
+quickcheck! {
+    fn into_par_chunks(inputs: Vec<(usize, char)>, chunks: u8) -> TestResult {
+        let chunks = chunks as usize;
+        if chunks == 0 {
+            return TestResult::discard();
+        }
+
+        let map: BiMap<_, _> = inputs.into_iter().collect();
+        let mut expected: Vec<_> = map.iter().map(|(&a, &b)| (a, b)).collect();
+        let len = map.len();
+
+        let split: Vec<Vec<_>> = map.into_par_chunks(chunks)
+            .into_iter()
+            .map(|chunk| chunk.collect())
+            .collect();
+        let balanced = split
+            .iter()
+            .all(|chunk| chunk.len() == len / chunks || chunk.len() == len / chunks + 1);
+        let split_len = split.len();
+        let mut pairs: Vec<_> = split.into_iter().flatten().collect();
+
+        expected.sort();
+        pairs.sort();
+
+        TestResult::from_bool(split_len == chunks && balanced && expected == pairs)
+    }
+}
+
+#[test]
+fn apply_deferred_half() {
+    let mut map: BiMap<usize, usize> = (0..100).map(|i| (i, i + 1000)).collect();
+
+    let mut removals = DeferredRemovals::new();
+    for (&left, &right) in &map {
+        if left % 4 == 0 {
+            removals.remove_left(left);
+        } else if left % 4 == 1 {
+            removals.remove_right(right);
+        }
+    }
+    assert_eq!(50, removals.len());
+
+    map.apply_deferred(removals);
+
+    assert_eq!(50, map.len());
+    for i in 0..100 {
+        if i % 4 < 2 {
+            assert_eq!(None, map.get_left(&i));
+            assert_eq!(None, map.get_right(&(i + 1000)));
+        } else {
+            assert_eq!(Some(&(i + 1000)), map.get_left(&i));
+            assert_eq!(Some(&i), map.get_right(&(i + 1000)));
+        }
+    }
+}
+
+quickcheck! {
+    fn content_hash_order_independent(inputs: Vec<(usize, char)>) -> bool {
+        let forwards: BiMap<_, _> = inputs.iter().cloned().collect();
+        let mut backwards: BiMap<_, _> = BiMapBuilder::new().capacity(1024).finish();
+        let mut pairs: Vec<_> = forwards.iter().map(|(&a, &b)| (a, b)).collect();
+        pairs.reverse();
+        backwards.extend(pairs);
+
+        forwards.content_hash() == backwards.content_hash()
+    }
+}
+
+#[test]
+fn content_hash_changes() {
+    let first: BiMap<usize, usize> = (0..50).map(|i| (i, i * 3)).collect();
+    let mut second: BiMap<usize, usize> = (0..50).rev().map(|i| (i, i * 3)).collect();
+    assert_eq!(first.content_hash(), second.content_hash());
+
+    second.insert(10, 1000);
+    assert_ne!(first.content_hash(), second.content_hash());
+
+    second.insert(10, 30);
+    assert_eq!(first.content_hash(), second.content_hash());
+
+    second.remove_left(&10);
+    assert_ne!(first.content_hash(), second.content_hash());
+}
+
+#[test]
+fn content_hash_is_stable() {
+    // the digest is fixed by the format, so it must not change between platforms or releases
+    let map: BiMap<usize, u32> = (0..50).map(|i| (i, i as u32 * 3)).collect();
+    assert_eq!(16_644_579_245_640_286_770, map.content_hash());
+}
+
+#[test]
+fn shared_str_keys_arc() {
+    let mut map: BiMap<Arc<str>, u32> = BiMap::new();
+    map.insert(Arc::from("Hello"), 5);
+    map.insert(Arc::from("World"), 7);
+
+    assert_eq!(Some(&5), map.get_left("Hello"));
+    assert_eq!(Some(&7), map.get_left("World"));
+    assert_eq!(None, map.get_left("Nope"));
+    assert_eq!(Some(&Arc::from("World")), map.get_right(&7));
+
+    assert_eq!(Some(5), map.remove_left("Hello"));
+    assert_eq!(None, map.get_left("Hello"));
+}
+
+#[test]
+fn shared_str_keys_rc() {
+    let mut map: BiMap<Rc<str>, u32> = BiMap::new();
+    map.insert(Rc::from("Hello"), 5);
+    map.insert(Rc::from("World"), 7);
+
+    assert_eq!(Some(&5), map.get_left("Hello"));
+    assert_eq!(Some(&7), map.get_left("World"));
+    assert_eq!(None, map.get_left("Nope"));
+    assert_eq!(Some(&Rc::from("World")), map.get_right(&7));
+
+    assert_eq!(Some(5), map.remove_left("Hello"));
+    assert_eq!(None, map.get_left("Hello"));
+}
+
+quickcheck! {
+    fn retain(inputs: Vec<(usize, char)>) -> bool {
+        let mut map: BiMap<_, _> = inputs.into_iter().collect();
+        let mut expected: Vec<_> = map
+            .iter()
+            .map(|(&a, &b)| (a, b))
+            .filter(|&(a, b)| a < b as usize)
+            .collect();
+
+        map.retain(|&a, &b| a < b as usize);
+        map.validate();
+
+        let mut pairs: Vec<_> = map.iter().map(|(&a, &b)| (a, b)).collect();
+        expected.sort();
+        pairs.sort();
+
+        expected == pairs
+    }
+}
+
+/// A right value with a weight, whose hash and equality only depend on its id.
+#[derive(Debug)]
+struct Weighted {
+    id: u64,
+    weight: u64,
+}
+
+impl PartialEq for Weighted {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for Weighted {}
+
+impl Hash for Weighted {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+#[test]
+fn retain_mut_doubles_and_drops() {
+    let mut map = BiMap::new();
+    for i in 0..100 {
+        map.insert(i, Weighted { id: i, weight: i });
+    }
+
+    map.retain_mut(|_, right| {
+        right.weight *= 2;
+        right.weight <= 100
+    });
+    map.validate();
+
+    assert_eq!(51, map.len());
+    for i in 0..100 {
+        if i <= 50 {
+            assert_eq!(i * 2, map.get_left(&i).unwrap().weight);
+            assert_eq!(Some(&i), map.get_right(&Weighted { id: i, weight: 0 }));
+        } else {
+            assert!(map.get_left(&i).is_none());
+            assert_eq!(None, map.get_right(&Weighted { id: i, weight: 0 }));
+        }
+    }
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "changed its hash")]
+fn retain_mut_detects_hash_change() {
+    let mut map: BiMap<u64, u64> = (0..10).map(|i| (i, i)).collect();
+    map.retain_mut(|_, right| {
+        *right += 100;
+        true
+    });
+}
+
+#[test]
+fn two_phase_insert() {
+    let mut map: BiMap<&str, u32> = BiMap::new();
+    map.insert("Hello", 5);
+
+    assert_eq!(None, map.insert_left_only("World"));
+    assert!(map.contains_left("World"));
+    assert_eq!(None, map.get_left("World"));
+    assert_eq!(1, map.len());
+    assert_eq!(vec![(&"Hello", &5)], map.iter().collect::<Vec<_>>());
+    assert_eq!(vec![&"World"], map.incomplete_lefts().collect::<Vec<_>>());
+
+    assert_eq!(Ok(None), map.complete_left("World", 7));
+    assert_eq!(Some(&7), map.get_left("World"));
+    assert_eq!(Some(&"World"), map.get_right(&7));
+    assert_eq!(2, map.len());
+    assert_eq!(0, map.incomplete_lefts().count());
+    map.validate();
+}
+
+#[test]
+fn two_phase_insert_existing() {
+    let mut map: BiMap<&str, u32> = BiMap::new();
+    map.insert("Hello", 5);
+    map.insert("World", 7);
+
+    // making a complete left key incomplete drops its old pair
+    assert_eq!(Some(5), map.insert_left_only("Hello"));
+    assert_eq!(None, map.get_right(&5));
+    assert_eq!(1, map.len());
+
+    // completing with a right key that is already paired steals it
+    assert_eq!(Ok(Some("World")), map.complete_left("Hello", 7));
+    assert_eq!(Some(&7), map.get_left("Hello"));
+    assert!(!map.contains_left("World"));
+    assert_eq!(1, map.len());
+
+    // only incomplete keys can be completed
+    assert_eq!(Err(9), map.complete_left("Hello", 9));
+    assert_eq!(Err(9), map.complete_left("Nope", 9));
+    map.validate();
+}
+
+#[test]
+fn two_phase_insert_overridden() {
+    let mut map: BiMap<&str, u32> = BiMap::new();
+
+    map.insert_left_only("Hello");
+    map.insert("Hello", 5);
+    assert_eq!(0, map.incomplete_lefts().count());
+    assert_eq!(Some(&5), map.get_left("Hello"));
+
+    map.insert_left_only("World");
+    assert_eq!(None, map.remove_left("World"));
+    assert!(!map.contains_left("World"));
+    assert_eq!(0, map.incomplete_lefts().count());
+}
+
+#[test]
+fn incomplete_lefts_survive_resizes() {
+    let mut map: BiMap<u64, u64> = BiMap::new();
+    for i in 0..50 {
+        map.insert_left_only(i);
+    }
+    map.extend((50..2000).map(|i| (i, i)));
+    map.validate();
+    assert_eq!(1950, map.len());
+    assert_eq!(50, map.incomplete_lefts().count());
+    assert!(map.contains_left(&10));
+    assert_eq!(None, map.get_left(&10));
+
+    // the incomplete left keys are moved into the smaller buckets along with the pairs
+    for i in 100..2000 {
+        map.remove_left(&i);
+    }
+    let before = map.memory_usage();
+    while !map.shrink_incremental(10) {
+        map.validate();
+        assert!(map.contains_left(&10));
+        assert_eq!(Some(&60), map.get_left(&60));
+    }
+    map.validate();
+    assert!(map.memory_usage() < before);
+    assert_eq!(50, map.len());
+    assert_eq!(50, map.incomplete_lefts().count());
+
+    for i in 0..50 {
+        assert_eq!(Ok(None), map.complete_left(&i, i));
+    }
+    map.validate();
+    assert_eq!(100, map.len());
+    assert_eq!(0, map.incomplete_lefts().count());
+}
+
+#[test]
+fn incomplete_lefts_are_not_compared() {
+    let mut first: BiMap<u64, u64> = (0..10).map(|i| (i, i)).collect();
+    let second: BiMap<u64, u64> = (0..10).map(|i| (i, i)).collect();
+    first.insert_left_only(20);
+
+    assert_eq!(first, second);
+    assert_eq!(first.content_hash(), second.content_hash());
+    assert_eq!(10, first.iter().count());
+    assert_eq!(10, first.into_iter().count());
+}
+
+#[test]
+fn with_capacity_zero() {
+    let mut map: BiMap<usize, char> = BiMapBuilder::new().capacity(0).finish();
+    assert_eq!(None, map.get_left(&1));
+
+    map.insert(1, 'a');
+    assert_eq!(Some(&'a'), map.get_left(&1));
+
+    let mut map: BiMap<usize, char> = BiMap::with_capacity(0);
+    assert_eq!(None, map.remove_right(&'a'));
+    map.insert(1, 'a');
+    assert_eq!(Some(&1), map.get_right(&'a'));
+}
+
+#[test]
+fn with_capacity_nonzero() {
+    let mut map = BiMap::with_capacity_nonzero(NonZeroUsize::new(1).unwrap());
+    assert!(map.capacity() >= 1);
+
+    map.insert(1, 'a');
+    assert_eq!(Some(&'a'), map.get_left(&1));
+    assert_eq!(Some(&1), map.get_right(&'a'));
+
+    // the hashmap still grows as normal
+    for (i, c) in "bcdefghijklmnopqrstuvwxyz".chars().enumerate() {
+        map.insert(i + 2, c);
+    }
+    assert_eq!(26, map.len());
+    assert_eq!(Some(&'z'), map.get_left(&26));
+    map.validate();
+}
+
+#[test]
+fn default_hasher_is_random_state() {
+    // this has to compile whether or not the debug-deterministic feature is enabled
+    let mut map: BiMap<u64, u64, RandomState, RandomState> = BiMap::new();
+    map.insert(1, 2);
+    let map: BiMap<u64, u64> = map;
+    assert_eq!(Some(&2), map.get_left(&1));
+}
+
+#[cfg(feature = "debug-deterministic")]
+#[test]
+fn deterministic_iteration() {
+    let mut first = BiMap::new();
+    let mut second = BiMap::new();
+
+    for i in 0..1000 {
+        first.insert(i, i.to_string());
+        second.insert(i, i.to_string());
+    }
+
+    assert!(first.iter().eq(second.iter()));
+}
+
+#[test]
+fn replace_fresh() {
+    let mut map = BiMap::new();
+    map.insert(1, 'a');
+
+    assert_eq!(None, map.replace(2, 'b'));
+    assert_eq!(2, map.len());
+    assert_eq!(Some(&'b'), map.get_left(&2));
+}
+
+#[test]
+fn replace_left_collision() {
+    let mut map = BiMap::new();
+    map.insert(1, 'a');
+
+    assert_eq!(Some((1, 'a')), map.replace(1, 'b'));
+    assert_eq!(1, map.len());
+    assert_eq!(Some(&'b'), map.get_left(&1));
+    assert_eq!(None, map.get_right(&'a'));
+}
+
+#[test]
+fn replace_right_collision() {
+    let mut map = BiMap::new();
+    map.insert(1, 'a');
+
+    assert_eq!(Some((1, 'a')), map.replace(2, 'a'));
+    assert_eq!(1, map.len());
+    assert_eq!(Some(&2), map.get_right(&'a'));
+    assert_eq!(None, map.get_left(&1));
+}
+
+#[test]
+fn replace_both_collisions() {
+    let mut map = BiMap::new();
+    map.insert(1, 'a');
+    map.insert(2, 'b');
+
+    assert_eq!(Some((1, 'a')), map.replace(1, 'b'));
+    assert_eq!(1, map.len());
+    assert_eq!(Some(&'b'), map.get_left(&1));
+    assert_eq!(None, map.get_left(&2));
+    assert_eq!(None, map.get_right(&'a'));
+}
+
+#[test]
+fn replace_same_pair() {
+    let mut map = BiMap::new();
+    map.insert(1, 'a');
+
+    assert_eq!(Some((1, 'a')), map.replace(1, 'a'));
+    assert_eq!(1, map.len());
+    assert_eq!(Some(&'a'), map.get_left(&1));
+}
+
+#[test]
+fn iter_with_right_index_round_trip() {
+    let map: BiMap<String, usize> = (0..100).map(|i| (format!("node {}", i), i)).collect();
+
+    // an index from slot to node label, like an adjacency structure would use
+    let mut labels = Vec::new();
+    for (left, &right, index) in map.iter_with_right_index() {
+        if labels.len() <= index {
+            labels.resize(index + 1, None);
+        }
+        assert!(labels[index].is_none(), "slot {} used twice", index);
+        labels[index] = Some((left.clone(), right));
+    }
+
+    assert_eq!(100, labels.iter().filter(|label| label.is_some()).count());
+    for (index, label) in labels.into_iter().enumerate() {
+        match label {
+            Some((left, right)) => {
+                assert_eq!(Some((&left, &right)), map.pair_at_right_index(index))
+            }
+            None => assert_eq!(None, map.pair_at_right_index(index)),
+        }
+    }
+}
+
+/// A key that records when it is dropped, for checking the order in which a bimap drops its keys.
+struct DropLogger {
+    name: String,
+    log: Rc<RefCell<Vec<String>>>,
+}
+
+impl PartialEq for DropLogger {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
+}
+
+impl Eq for DropLogger {}
+
+impl Hash for DropLogger {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.name.hash(state);
+    }
+}
+
+impl DropLogger {
+    fn pairs(log: &Rc<RefCell<Vec<String>>>, count: usize) -> Vec<(DropLogger, DropLogger)> {
+        (0..count)
+            .map(|i| {
+                let left = DropLogger {
+                    name: format!("left {}", i),
+                    log: log.clone(),
+                };
+                let right = DropLogger {
+                    name: format!("right {}", i),
+                    log: log.clone(),
+                };
+                (left, right)
+            })
+            .collect()
+    }
+
+    fn lefts_dropped_first(log: &Rc<RefCell<Vec<String>>>, lefts: usize, rights: usize) -> bool {
+        let log = log.borrow();
+        log.len() == lefts + rights
+            && log[..lefts].iter().all(|name| name.starts_with("left"))
+            && log[lefts..].iter().all(|name| name.starts_with("right"))
+    }
+}
+
+impl Drop for DropLogger {
+    fn drop(&mut self) {
+        self.log.borrow_mut().push(self.name.clone());
+    }
+}
+
+#[test]
+fn drop_order_drop() {
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let mut map: BiMap<_, _> = DropLogger::pairs(&log, 20).into_iter().collect();
+    map.insert_left_only(DropLogger {
+        name: "left incomplete".to_owned(),
+        log: log.clone(),
+    });
+
+    drop(map);
+    assert!(DropLogger::lefts_dropped_first(&log, 21, 20));
+}
+
+#[test]
+fn drop_order_clear() {
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let mut map: BiMap<_, _> = DropLogger::pairs(&log, 20).into_iter().collect();
+
+    map.clear();
+    assert!(map.is_empty());
+    assert!(DropLogger::lefts_dropped_first(&log, 20, 20));
+}
+
+#[test]
+fn drop_order_drain() {
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let mut map: BiMap<_, _> = DropLogger::pairs(&log, 20).into_iter().collect();
+
+    let mut drain = map.drain();
+    drop(drain.next());
+    drop(drain.next());
+    log.borrow_mut().clear();
+    drop(drain);
+
+    assert!(map.is_empty());
+    assert!(DropLogger::lefts_dropped_first(&log, 18, 18));
+}
+
+#[test]
+fn drop_order_into_iter() {
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let map: BiMap<_, _> = DropLogger::pairs(&log, 20).into_iter().collect();
+
+    let mut iter = map.into_iter();
+    drop(iter.next());
+    log.borrow_mut().clear();
+    drop(iter);
+
+    assert!(DropLogger::lefts_dropped_first(&log, 19, 19));
+}
+
+#[test]
+fn clear_releases_shared_keys() {
+    let lefts: Vec<_> = (0..50).map(Rc::new).collect();
+    let rights: Vec<_> = (0..50).map(|i| Rc::new(i.to_string())).collect();
+
+    let mut map: BiMap<_, _> = lefts.iter().cloned().zip(rights.iter().cloned()).collect();
+    assert!(lefts.iter().all(|left| Rc::strong_count(left) == 2));
+    assert!(rights.iter().all(|right| Rc::strong_count(right) == 2));
+
+    map.clear();
+    assert!(lefts.iter().all(|left| Rc::strong_count(left) == 1));
+    assert!(rights.iter().all(|right| Rc::strong_count(right) == 1));
+
+    // the hashmap is still usable after being cleared
+    map.insert(lefts[0].clone(), rights[0].clone());
+    assert_eq!(Some(&rights[0]), map.get_left(&lefts[0]));
+}
+
+#[test]
+fn remove_by_valid_handle() {
+    let mut map = BiMap::with_capacity(1000);
+    for i in 0..100u64 {
+        map.insert(i, i.to_string());
+    }
+
+    let handles: Vec<_> = (0..100u64).map(|i| map.handle_left(&i).unwrap()).collect();
+    let capacity = map.capacity();
+
+    // removals never move the pairs that remain
+    for i in 50..100u64 {
+        map.remove_left(&i);
+    }
+    assert_eq!(capacity, map.capacity());
+
+    for (i, &handle) in handles.iter().enumerate().take(50) {
+        assert_eq!(Some((i as u64, i.to_string())), map.remove_by_handle(handle));
+        map.validate();
+    }
+    assert!(map.is_empty());
+
+    // the handles of removed pairs are rejected
+    assert_eq!(None, map.remove_by_handle(handles[75]));
+    assert_eq!(None, map.remove_by_handle(handles[0]));
+}
+
+#[test]
+fn remove_by_handle_rejected_after_resize() {
+    let mut map = BiMap::new();
+    map.insert(0u64, 0u64);
+    let handle = map.handle_left(&0).unwrap();
+
+    let capacity = map.capacity();
+    let mut next = 1;
+    while map.capacity() == capacity {
+        map.insert(next, next);
+        next += 1;
+    }
+
+    assert_eq!(None, map.remove_by_handle(handle));
+    assert_eq!(Some(&0), map.get_left(&0));
+    assert_eq!(next as usize, map.len());
+}
+
+#[test]
+fn remove_by_handle_rejected_after_slot_reuse() {
+    let mut map = BiMap::new();
+    map.insert(0u64, 0u64);
+    let stale = map.handle_left(&0).unwrap();
+    assert_eq!(Some((0, 0)), map.remove_by_handle(stale));
+
+    // the same pair is stored in the same buckets again, but the old handle still doesn't apply
+    map.insert(0, 0);
+    let fresh = map.handle_left(&0).unwrap();
+    assert_eq!(None, map.remove_by_handle(stale));
+    assert_eq!(Some(&0), map.get_left(&0));
+
+    assert_eq!(Some((0, 0)), map.remove_by_handle(fresh));
+    assert!(map.is_empty());
+}
+
+quickcheck! {
+    fn ord_consistent_with_eq(first: Vec<(u8, u8)>, second: Vec<(u8, u8)>) -> bool {
+        let first: BiMap<_, _> = first.into_iter().collect();
+        let second: BiMap<_, _> = second.into_iter().collect();
+        let reversed: BiMap<_, _> = {
+            let mut pairs: Vec<_> = first.iter().map(|(&a, &b)| (a, b)).collect();
+            pairs.reverse();
+            pairs.into_iter().collect()
+        };
+
+        (first == second) == (first.cmp(&second) == Ordering::Equal)
+            && first.cmp(&second) == second.cmp(&first).reverse()
+            && first.cmp(&reversed) == Ordering::Equal
+    }
+}
+
+#[test]
+fn btree_set_deduplicates_by_content() {
+    let forwards: BiMap<u32, char> = (0..10).map(|i| (i, (b'a' + i as u8) as char)).collect();
+    let backwards: BiMap<u32, char> =
+        (0..10).rev().map(|i| (i, (b'a' + i as u8) as char)).collect();
+    let mut remapped = forwards.iter().map(|(&l, &r)| (l, r)).collect::<BiMap<_, _>>();
+    remapped.insert(3, 'z');
+    let empty: BiMap<u32, char> = BiMap::new();
+
+    let set: BTreeSet<_> = vec![forwards, remapped, backwards, BiMap::new(), empty]
+        .into_iter()
+        .collect();
+    assert_eq!(3, set.len());
+
+    let ordered: Vec<_> = set.iter().map(|map| map.len()).collect();
+    assert_eq!(vec![0, 10, 10], ordered);
+    assert_eq!(Some(&'z'), set.iter().last().unwrap().get_left(&3));
+}
+
+/// Creates a bimap that hashes both of its sides with `IdentityHasher`.
+fn identity_map() -> BiMap<u64, u64, IdentityBuildHasher, IdentityBuildHasher> {
+    BiMapBuilder::new()
+        .left_hasher(IdentityBuildHasher::default())
+        .right_hasher(IdentityBuildHasher::default())
+        .finish()
+}
+
+#[test]
+fn lookups_with_empty_home_bucket() {
+    let mut map = identity_map();
+    // only even buckets are home to any keys, so every odd bucket has an empty neighbourhood
+    for i in (0..32).step_by(2) {
+        map.insert(i, i + 1000);
+    }
+
+    for i in (1..32).step_by(2) {
+        assert_eq!(None, map.get_left(&i));
+        assert_eq!(None, map.get_right(&(i + 1000)));
+        assert_eq!(None, map.remove_left(&i));
+        assert_eq!(None, map.remove_right(&(i + 1000)));
+        assert_eq!(None, map.handle_left(&i));
+    }
+
+    assert_eq!(16, map.len());
+    for i in (0..32).step_by(2) {
+        assert_eq!(Some(&(i + 1000)), map.get_left(&i));
+        assert_eq!(Some(&i), map.get_right(&(i + 1000)));
+    }
+    map.validate();
+}
+
+#[test]
+fn sorted_pairs_canonical() {
+    let forwards: BiMap<String, u64> = (0..200).map(|i| (format!("key {}", i), i)).collect();
+
+    let mut backwards: BiMap<String, u64> = BiMapBuilder::new().capacity(4096).finish();
+    for i in (0..300).rev() {
+        backwards.insert(format!("key {}", i), i);
+    }
+    for i in 200..300 {
+        backwards.remove_right(&i);
+    }
+
+    assert!(forwards == backwards);
+    let sorted = forwards.sorted_pairs();
+    assert_eq!(sorted, backwards.sorted_pairs());
+    assert_eq!(200, sorted.len());
+    assert!(sorted.windows(2).all(|pairs| pairs[0].0 < pairs[1].0));
+}
+
+#[test]
+fn lru_evicts_oldest() {
+    let mut map = BiMap::with_lru_capacity(100);
+    for i in 0..100u64 {
+        assert_eq!((None, None, None), map.insert_evicting(i, i + 1000));
+    }
+    assert_eq!(100, map.len());
+
+    assert_eq!((None, None, Some((0, 1000))), map.insert_evicting(100, 1100));
+    assert_eq!(100, map.len());
+    assert_eq!(None, map.get_left(&0));
+    assert_eq!(None, map.get_right(&1000));
+    map.validate();
+
+    // reinserting a pair moves it to the back of the queue
+    map.insert(1, 1001);
+    assert_eq!((None, None, Some((2, 1002))), map.insert_evicting(101, 1101));
+    assert_eq!(Some(&1001), map.get_left(&1));
+
+    // an insert that replaces existing pairs doesn't need to evict anything
+    assert_eq!((Some(1003), Some(4), None), map.insert_evicting(3, 1004));
+    assert_eq!(99, map.len());
+    map.validate();
+}
+
+#[test]
+fn lru_through_resizes() {
+    let mut map = BiMap::with_lru_capacity(1000);
+    for i in 0..5000u64 {
+        let (_, _, evicted) = map.insert_evicting(i, i.to_string());
+        if i < 1000 {
+            assert_eq!(None, evicted);
+        } else {
+            assert_eq!(Some((i - 1000, (i - 1000).to_string())), evicted);
+        }
+    }
+    map.validate();
+
+    let order: Vec<_> = map.iter_ordered().unwrap().map(|(&left, _)| left).collect();
+    assert_eq!((4000..5000).collect::<Vec<_>>(), order);
+}
+
+#[test]
+fn ordered_survives_resizes_and_removals() {
+    let mut map: BiMap<u64, u64, _, _, u8> =
+        BiMapBuilder::new().capacity(1).ordered().bitfield::<u8>().finish();
+    for i in 0..2000 {
+        map.insert(i * 7 % 2000, i);
+    }
+    for i in 0..1000 {
+        map.remove_right(&(i * 2));
+    }
+    map.try_reserve_exact(10_000).unwrap();
+    map.validate();
+
+    let order: Vec<_> = map.iter_ordered().unwrap().map(|(_, &right)| right).collect();
+    assert_eq!((0..1000).map(|i| i * 2 + 1).collect::<Vec<_>>(), order);
+}
+
+/// A right value whose hash and equality only depend on its id.
+#[derive(Debug)]
+struct Tagged {
+    id: u64,
+    tag: &'static str,
+}
+
+impl PartialEq for Tagged {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for Tagged {}
+
+impl Hash for Tagged {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+#[test]
+fn borrow_right_mut_in_place() {
+    let mut map = BiMap::new();
+    for i in 0..50 {
+        map.insert(i, Tagged { id: i * 10, tag: "old" });
+    }
+
+    for i in (0..50).step_by(2) {
+        let mut right = map.borrow_right_mut(&i).unwrap();
+        assert_eq!(i * 10, right.id);
+        right.tag = "new";
+    }
+    map.validate();
+
+    for i in 0..50 {
+        let right = map.get_left(&i).unwrap();
+        assert_eq!(if i % 2 == 0 { "new" } else { "old" }, right.tag);
+        assert_eq!(Some(&i), map.get_right(&Tagged { id: i * 10, tag: "any" }));
+    }
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "changed its hash")]
+fn borrow_right_mut_detects_hash_change() {
+    let mut map = BiMap::new();
+    map.insert(1, Tagged { id: 10, tag: "old" });
+
+    let mut right = map.borrow_right_mut(&1).unwrap();
+    right.id = 11;
+}
+
+#[test]
+#[cfg(feature = "rayon")]
+fn into_par_iter_owned() {
+    use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+    let map: BiMap<u64, String> = (0..10_000).map(|i| (i, i.to_string())).collect();
+    let mut pairs: Vec<_> = map.into_par_iter().collect();
+    assert_eq!(10_000, pairs.len());
+
+    pairs.sort();
+    pairs.dedup();
+    assert_eq!(10_000, pairs.len());
+    assert!(pairs.iter().all(|&(left, ref right)| *right == left.to_string()));
+}
+
+quickcheck! {
+    fn for_each_pair_matches_iter(pairs: Vec<(u32, u32)>) -> bool {
+        let map: BiMap<_, _> = pairs.into_iter().collect();
+
+        let mut visited = 0;
+        let mut total = 0u64;
+        map.for_each_pair(|_, &right| {
+            visited += 1;
+            total += u64::from(right);
+        });
+
+        visited == map.len() && total == map.iter().map(|(_, &right)| u64::from(right)).sum::<u64>()
+    }
+}
+
+#[test]
+fn retain_keeps_insertion_order() {
+    let mut map: BiMap<u64, u64> = BiMapBuilder::new().ordered().finish();
+    for i in (0..20).rev() {
+        map.insert(i, i * 100);
+    }
+
+    map.retain(|&left, _| left % 3 == 0);
+    map.validate();
+    let survivors: Vec<_> = map
+        .iter_ordered()
+        .unwrap()
+        .map(|(&left, &right)| (left, right))
+        .collect();
+    assert_eq!(
+        vec![(18, 1800), (15, 1500), (12, 1200), (9, 900), (6, 600), (3, 300), (0, 0)],
+        survivors
+    );
+
+    map.retain_mut(|&left, _| left != 9);
+    map.validate();
+    let survivors: Vec<_> = map.iter_ordered().unwrap().map(|(&left, _)| left).collect();
+    assert_eq!(vec![18, 15, 12, 6, 3, 0], survivors);
+    assert_eq!(Some(&1200), map.get_left(&12));
+
+    let mut removals = DeferredRemovals::new();
+    removals.remove_left(15);
+    removals.remove_right(0);
+    map.apply_deferred(removals);
+    map.validate();
+
+    map.insert(100, 100);
+    let survivors: Vec<_> = map.iter_ordered().unwrap().map(|(&left, _)| left).collect();
+    assert_eq!(vec![18, 12, 6, 3, 100], survivors);
+}
+
+#[test]
+fn shrink_to_fit_reports_freed_bytes() {
+    let mut map: BiMap<u64, String> = BiMapBuilder::new().ordered().finish();
+    for i in 0..10_000 {
+        map.insert(i, i.to_string());
+    }
+    map.retain(|&left, _| left < 100);
+    let capacity = map.capacity();
+
+    let before = map.memory_usage();
+    let freed = map.shrink_to_fit();
+    assert!(freed > 0);
+    assert_eq!(before - freed, map.memory_usage());
+    assert!(map.capacity() < capacity / 10);
+    assert!(map.capacity() >= 100);
+
+    map.validate();
+    assert_eq!(100, map.len());
+    for i in 0..100 {
+        assert_eq!(Some(&i.to_string()), map.get_left(&i));
+    }
+    let order: Vec<_> = map.iter_ordered().unwrap().map(|(&left, _)| left).collect();
+    assert_eq!((0..100).collect::<Vec<_>>(), order);
+
+    // shrinking never grows the hashmap
+    let capacity = map.capacity();
+    assert_eq!(0, map.shrink_to(1_000_000));
+    assert_eq!(capacity, map.capacity());
+}
+
+#[test]
+fn shrink_incremental_spreads_over_calls() {
+    let mut map: BiMap<u64, String> = BiMap::with_capacity(10_000);
+    for i in 0..1000 {
+        map.insert(i, i.to_string());
+    }
+    let capacity = map.capacity();
+
+    let mut calls = 0;
+    loop {
+        calls += 1;
+        let finished = map.shrink_incremental(100);
+        map.validate();
+        assert_eq!(1000, map.len());
+        assert_eq!(1000, map.iter().count());
+        for i in 0..1000 {
+            assert_eq!(Some(&i.to_string()), map.get_left(&i));
+            assert_eq!(Some(&i), map.get_right(&i.to_string()));
+        }
+        if finished {
+            break;
+        }
+    }
+
+    assert_eq!(10, calls);
+    // the smaller buckets may have grown once if a neighbourhood overflowed while shrinking
+    assert!(map.capacity() >= 1000 && map.capacity() < capacity / 4);
+    // there is nothing left to shrink
+    assert!(map.shrink_incremental(1));
+}
+
+#[test]
+fn shrink_incremental_allows_modification_between_calls() {
+    let mut map: BiMap<u64, u64> = BiMap::with_capacity(10_000);
+    for i in 0..1000 {
+        map.insert(i, i);
+    }
+    assert!(!map.shrink_incremental(300));
+
+    // pairs still in the old buckets are replaced and removed just like the others
+    for i in 0..100 {
+        assert_eq!((Some(i), None), map.insert(i, i + 1000));
+    }
+    for i in 100..200 {
+        assert_eq!(Some(i), map.remove_left(&i));
+    }
+    let handle = map.handle_left(&999).unwrap();
+    assert_eq!(Some((999, 999)), map.remove_by_handle(handle));
+    map.validate();
+    assert!(!map.shrink_incremental(300));
+
+    let mut pairs: Vec<_> = map.iter().map(|(&left, &right)| (left, right)).collect();
+    pairs.sort();
+    let expected: Vec<_> = (0..100)
+        .map(|i| (i, i + 1000))
+        .chain((200..999).map(|i| (i, i)))
+        .collect();
+    assert_eq!(expected, pairs);
+
+    // another kind of modification finishes the shrink, freeing the old buckets
+    let during = map.memory_usage();
+    map.retain(|_, _| true);
+    assert!(map.memory_usage() < during);
+    map.validate();
+    assert_eq!(899, map.len());
+}
+
+#[test]
+fn overwriting_insert_removes_colliding_pairs() {
+    let mut map = BiMap::new();
+    map.insert(1, 'a');
+    map.insert(2, 'b');
+
+    // collides with both existing pairs
+    assert_eq!((Some('a'), Some(2)), map.insert(1, 'b'));
+    assert_eq!(1, map.len());
+    assert_eq!(Some(&'b'), map.get_left(&1));
+    assert_eq!(None, map.get_left(&2));
+    map.validate();
+}
+
+#[test]
+fn overwrite_insert_replaces_colliding_pairs() {
+    let mut map: OverwriteBiMap<u64, char> = OverwriteBiMap::new();
+    assert_eq!((None, None), map.insert(1, 'a'));
+    assert_eq!((None, None), map.insert(2, 'b'));
+
+    assert_eq!((Some('a'), Some(2)), map.insert(1, 'b'));
+    assert_eq!((None, Some(1)), map.insert(3, 'b'));
+    assert_eq!(1, map.len());
+    assert_eq!(Some(&'b'), map.get_left(&3));
+    map.validate();
+}
+
+#[test]
+fn strict_insert_rejects_colliding_pairs() {
+    let mut map = StrictBiMap::new();
+    assert_eq!(Ok(()), map.insert(1, 'a'));
+    assert_eq!(Ok(()), map.insert(2, 'b'));
+
+    assert_eq!(Err((1, 'b')), map.insert(1, 'b'));
+    assert_eq!(Err((1, 'c')), map.insert(1, 'c'));
+    assert_eq!(Err((3, 'a')), map.insert(3, 'a'));
+    assert_eq!(2, map.len());
+    assert_eq!(Some(&'a'), map.get_left(&1));
+    assert_eq!(Some(&'b'), map.get_left(&2));
+
+    assert_eq!(Some('a'), map.remove_left(&1));
+    assert_eq!(Ok(()), map.insert(3, 'a'));
+    map.validate();
+
+    // incomplete left keys count as collisions too
+    let mut map = map.into_inner();
+    map.insert_left_only(4);
+    let mut map = StrictBiMap::from(map);
+    assert_eq!(Err((4, 'd')), map.insert(4, 'd'));
+    assert!(map.contains_left(&4));
+}
+
+quickcheck! {
+    fn from_sorted_unique_matches_from_iter(inputs: Vec<(u16, char)>) -> bool {
+        let mut sorted = inputs.clone();
+        sorted.sort_by_key(|&(left, _)| left);
+        sorted.dedup_by_key(|&mut (left, _)| left);
+
+        let fast: BiMap<_, _> = BiMap::from_sorted_unique(sorted.clone());
+        let collected: BiMap<_, _> = sorted.into_iter().collect();
+        fast.validate();
+
+        // a broken promise still builds the same map, just without the fast path
+        let unsorted: BiMap<_, _> = BiMap::from_sorted_unique(inputs.clone());
+        unsorted.validate();
+
+        fast == collected && unsorted == inputs.into_iter().collect::<BiMap<_, _>>()
+    }
+}
+
+#[test]
+fn from_sorted_unique_large_input() {
+    let map: BiMap<u64, u64> = BiMap::from_sorted_unique((0..10_000).map(|i| (i, i * 3)));
+    map.validate();
+    assert_eq!(10_000, map.len());
+    for i in 0..10_000 {
+        assert_eq!(Some(&(i * 3)), map.get_left(&i));
+    }
+}
+
+#[test]
+fn ref_iter_size_hint_is_exact() {
+    let mut map = BiMap::new();
+    assert_eq!((0, Some(0)), (&map).into_iter().size_hint());
+    for i in 0..100 {
+        map.insert(i, i * 2);
+    }
+    map.remove_left(&7);
+
+    let mut iter = (&map).into_iter();
+    assert_eq!((99, Some(99)), iter.size_hint());
+    iter.next();
+    assert_eq!(98, iter.len());
+    assert_eq!(98, iter.count());
+}
+
+/// Every ordering of a list of updates.
+fn permutations<T: Clone>(items: &[T]) -> Vec<Vec<T>> {
+    if items.is_empty() {
+        return vec![Vec::new()];
+    }
+    let mut output = Vec::new();
+    for i in 0..items.len() {
+        let mut rest = items.to_vec();
+        let item = rest.remove(i);
+        for mut permutation in permutations(&rest) {
+            permutation.insert(0, item.clone());
+            output.push(permutation);
+        }
+    }
+    output
+}
+
+#[test]
+fn merge_timestamped_newest_wins() {
+    let updates = [(1, 'a', 3), (1, 'b', 7), (1, 'c', 5), (2, 'd', 1), (2, 'e', 4)];
+    for updates in permutations(&updates) {
+        let mut map = TimestampedBiMap::new();
+        map.merge_timestamped(updates.clone());
+
+        let mut pairs: Vec<_> =
+            map.iter().map(|(&left, &right, stamp)| (left, right, stamp)).collect();
+        pairs.sort();
+        assert_eq!(vec![(1, 'b', 7), (2, 'e', 4)], pairs, "merging {:?}", updates);
+    }
+}
+
+#[test]
+fn merge_timestamped_right_conflicts() {
+    // both lefts want the same right, so only the newest pair survives
+    let updates = [(1, 'a', 2), (2, 'a', 6), (3, 'a', 4)];
+    for updates in permutations(&updates) {
+        let mut map = TimestampedBiMap::new();
+        map.merge_timestamped(updates);
+        assert_eq!(1, map.len());
+        assert_eq!(Some(&2), map.get_right(&'a'));
+        assert_eq!(Some(6), map.timestamp_right(&'a'));
+        assert_eq!(None, map.get_left(&1));
+        assert_eq!(None, map.get_left(&3));
+    }
+}
+
+#[test]
+fn get_left_arc_shares_refcount() {
+    let mut map = BiMap::new();
+    let value = Arc::new(String::from("World"));
+    map.insert("Hello", value.clone());
+    assert_eq!(2, Arc::strong_count(&value));
+
+    let read = map.get_left_arc("Hello").unwrap();
+    assert_eq!(3, Arc::strong_count(&value));
+    assert!(Arc::ptr_eq(&value, &read));
+
+    // the clone outlives both another borrow of the map and the pair itself
+    map.insert("Hashmaps", Arc::new(String::from("Bimaps")));
+    map.remove_left("Hello");
+    assert_eq!("World", *read);
+    assert_eq!(2, Arc::strong_count(&value));
+    assert_eq!(None, map.get_left_arc("Hello"));
+}
+
+#[test]
+fn retain_until_leaves_unvisited_pairs() {
+    let mut map: BiMap<u64, u64> = (0..1000).map(|i| (i, i * 2)).collect();
+    let mut visited = Vec::new();
+    map.retain_until(|&left, _| {
+        visited.push(left);
+        if visited.len() > 5 {
+            RetainControl::Stop
+        } else if left % 2 == 0 {
+            RetainControl::Remove
+        } else {
+            RetainControl::Keep
+        }
+    });
+    map.validate();
+
+    assert_eq!(6, visited.len());
+    let removed: Vec<_> = visited[..5].iter().filter(|&&left| left % 2 == 0).collect();
+    assert_eq!(1000 - removed.len(), map.len());
+    for i in 0..1000 {
+        if removed.contains(&&i) {
+            assert_eq!(None, map.get_left(&i));
+        } else {
+            assert_eq!(Some(&(i * 2)), map.get_left(&i));
+        }
+    }
+}
+
+#[test]
+fn entry_and_modify_or_insert() {
+    let mut map = BiMap::new();
+    map.insert("Hello", Tagged { id: 1, tag: "old" });
+
+    // present: modified in place, and the default is ignored
+    let right = map
+        .entry_left("Hello")
+        .and_modify(|right| right.tag = "new")
+        .or_insert(Tagged { id: 9, tag: "old" });
+    assert_eq!((1, "new"), (right.id, right.tag));
+
+    // absent: nothing to modify, so the default is inserted
+    let right = map
+        .entry_left("World")
+        .and_modify(|right| right.tag = "new")
+        .or_insert(Tagged { id: 2, tag: "old" });
+    assert_eq!((2, "old"), (right.id, right.tag));
+
+    assert_eq!(2, map.len());
+    assert_eq!("new", map.get_left("Hello").unwrap().tag);
+    assert_eq!(Some(&"World"), map.get_right(&Tagged { id: 2, tag: "old" }));
+    assert_eq!(None, map.get_right(&Tagged { id: 9, tag: "old" }));
+    map.validate();
+}
+
+#[test]
+fn get_left_where_by_parts() {
+    let mut map = BiMap::new();
+    for a in 0..50u32 {
+        for b in 0..50u32 {
+            map.insert((a, b), a * 100 + b);
+        }
+    }
+
+    let hash_parts = |a: u32, b: u32| {
+        let mut hasher = map.left_hasher().build_hasher();
+        a.hash(&mut hasher);
+        b.hash(&mut hasher);
+        hasher.finish()
+    };
+    for a in 0..50u32 {
+        for b in 0..50u32 {
+            let hash = hash_parts(a, b);
+            assert_eq!(hash, map.left_hasher().hash_one((a, b)));
+            let right = map.get_left_where(hash, |&(x, y)| x == a && y == b);
+            assert_eq!(Some(&(a * 100 + b)), right);
+        }
+    }
+    assert_eq!(None, map.get_left_where(hash_parts(50, 0), |&(x, y)| x == 50 && y == 0));
+}
+
+#[test]
+fn diff_added_removed_changed() {
+    let mut old = BiMap::new();
+    for i in 0..10 {
+        old.insert(i, i * 10);
+    }
+    let mut new = BiMap::new();
+    for i in 0..10 {
+        new.insert(i, i * 10);
+    }
+    new.insert(10, 100);
+    new.remove_left(&3);
+    new.insert(5, 55);
+
+    let diff = old.diff(&new);
+    assert_eq!(vec![(&10, &100)], diff.added);
+    assert_eq!(vec![(&3, &30)], diff.removed);
+    assert_eq!(vec![(&5, &50, &55)], diff.changed);
+
+    // the other way around, additions and removals swap
+    let diff = new.diff(&old);
+    assert_eq!(vec![(&3, &30)], diff.added);
+    assert_eq!(vec![(&10, &100)], diff.removed);
+    assert_eq!(vec![(&5, &55, &50)], diff.changed);
+
+    assert!(old.diff(&old).is_empty());
+}
+
+quickcheck! {
+    fn apply_diff_reaches_target(old: Vec<(u8, u8)>, new: Vec<(u8, u8)>) -> bool {
+        let mut old: BiMap<u8, u8> = old.into_iter().collect();
+        let new: BiMap<u8, u8> = new.into_iter().collect();
+
+        let diff = old.diff(&new).cloned();
+        old.apply_diff(diff);
+        old.validate();
+        old == new
+    }
+}
+
+#[test]
+fn clear_reseed_changes_layout() {
+    let mut map = BiMap::new();
+    for i in 0..100u64 {
+        map.insert(i, i);
+    }
+    let before: Vec<_> = map.iter().map(|(&left, _)| left).collect();
+
+    map.clear_reseed();
+    assert!(map.is_empty());
+    assert_eq!(None, map.get_left(&0));
+
+    for i in 0..100u64 {
+        map.insert(i, i);
+    }
+    map.validate();
+    let after: Vec<_> = map.iter().map(|(&left, _)| left).collect();
+
+    // the chance of 100 keys being arranged the same way by two different seeds is negligible
+    assert_ne!(before, after);
+}
+
+#[test]
+fn insert_ref_returns_stored_pair() {
+    let mut map = BiMap::new();
+    for i in 0..1000u64 {
+        let (left, right) = map.insert_ref(i, i.to_string());
+        assert_eq!((&i, &i.to_string()), (left, right));
+    }
+
+    // overwriting both keys of other pairs still returns the pair that was stored
+    let (left, right) = map.insert_ref(5, String::from("1"));
+    assert_eq!((&5, "1"), (left, right.as_str()));
+    let right = right as *const String;
+    assert_eq!(right, map.get_left(&5).unwrap() as *const String);
+    assert_eq!(999, map.len());
+    map.validate();
+}
+
+#[test]
+fn on_resize_records_transitions() {
+    let transitions = Arc::new(Mutex::new(Vec::new()));
+    let mut map = BiMap::new();
+    let log = transitions.clone();
+    map.on_resize(Box::new(move |old, new| log.lock().unwrap().push((old, new))));
+
+    let initial = map.capacity();
+    for i in 0..10_000u64 {
+        map.insert(i, i);
+    }
+    map.retain(|&left, _| left < 10);
+    map.shrink_to_fit();
+
+    let transitions = transitions.lock().unwrap();
+    assert!(transitions.len() >= 3);
+    // growing doubles the buckets, and each resize starts where the last one finished
+    let (last, grows) = transitions.split_last().unwrap();
+    assert!(grows[0].0 >= initial);
+    for &(old, new) in grows {
+        assert_eq!(old * 2, new);
+    }
+    for pair in transitions.windows(2) {
+        assert_eq!(pair[0].1, pair[1].0);
+    }
+    assert!(last.1 < last.0);
+}
+
+#[test]
+fn total_f64_nan_keys() {
+    use isomorphism::float::TotalF64;
+
+    let mut map = BiMap::new();
+    map.insert(TotalF64(f64::NAN), 1);
+    map.insert(TotalF64(0.0), 2);
+    map.insert(TotalF64(1.5), 3);
+    assert_eq!(3, map.len());
+
+    // NaNs with any sign or payload are the same key, as are both zeroes
+    let other_nan = TotalF64(f64::from_bits(f64::NAN.to_bits() | 1));
+    assert!(other_nan.0.is_nan());
+    assert_eq!(Some(&1), map.get_left(&other_nan));
+    assert_eq!(Some(&1), map.get_left(&TotalF64(-f64::NAN)));
+    assert_eq!(Some(&2), map.get_left(&TotalF64(-0.0)));
+
+    // replacing a NaN with another NaN overwrites rather than duplicating
+    assert_eq!((Some(1), None), map.insert(other_nan, 4));
+    assert_eq!(3, map.len());
+    assert_eq!(Some(TotalF64(f64::NAN)), map.remove_right(&4));
+    assert_eq!(None, map.get_left(&TotalF64(f64::NAN)));
+    assert_eq!(2, map.len());
+    map.validate();
+}
+
+#[test]
+fn total_f64_ordering() {
+    use isomorphism::float::TotalF64;
+
+    let values = [f64::NAN, 1.0, f64::NEG_INFINITY, -0.0, f64::INFINITY];
+    let mut values: Vec<_> = values.iter().map(|&value| TotalF64(value)).collect();
+    values.sort();
+    let sorted: Vec<f64> = values.into_iter().map(f64::from).collect();
+    assert_eq!(&[f64::NEG_INFINITY, -0.0, 1.0, f64::INFINITY], &sorted[..4]);
+    assert!(sorted[4].is_nan());
+    assert_eq!(Ordering::Equal, TotalF64(0.0).cmp(&TotalF64(-0.0)));
+}
+
+#[test]
+fn export_pages_cover_map() {
+    let map: BiMap<u64, u64> = (0..1000).map(|i| (i, i * 2)).collect();
+    let mut cursor = map.export_cursor();
+    let mut seen = HashSet::new();
+    let mut pages = 0;
+    loop {
+        let page = map.export_page(&mut cursor, 64).unwrap();
+        if page.is_empty() {
+            break;
+        }
+        assert!(page.len() <= 64);
+        for (&left, &right) in page {
+            assert_eq!(left * 2, right);
+            assert!(seen.insert(left));
+        }
+        pages += 1;
+    }
+    assert_eq!(1000, seen.len());
+    assert!(pages >= 1000 / 64);
+
+    // a finished cursor stays finished
+    assert_eq!(Ok(Vec::new()), map.export_page(&mut cursor, 64));
+}
+
+#[test]
+fn export_cursor_rejected_after_mutation() {
+    let mut map: BiMap<u64, u64> = (0..100).map(|i| (i, i)).collect();
+    let mut cursor = map.export_cursor();
+    assert_eq!(10, map.export_page(&mut cursor, 10).unwrap().len());
+
+    map.remove_left(&50);
+    assert_eq!(Err(ExportError::Modified), map.export_page(&mut cursor, 10));
+
+    // removing a key that isn't there doesn't modify anything
+    let mut cursor = map.export_cursor();
+    map.remove_left(&50);
+    map.remove_right(&50);
+    assert!(map.export_page(&mut cursor, 10).is_ok());
+
+    map.insert(100, 100);
+    assert_eq!(Err(ExportError::Modified), map.export_page(&mut cursor, 10));
+
+    let mut cursor = map.export_cursor();
+    map.clear();
+    assert_eq!(Err(ExportError::Modified), map.export_page(&mut cursor, 10));
+}
+
+#[test]
+fn canonical_left_returns_stored_key() {
+    let mut map = BiMap::new();
+    map.insert(String::from("Hello"), 5);
+    map.insert(String::from("World"), 6);
+
+    let query = String::from("Hello");
+    let canonical = map.canonical_left(&query).unwrap();
+    let (stored, _) = map.iter().find(|&(left, _)| left == "Hello").unwrap();
+    assert!(ptr::eq(stored, canonical));
+    assert!(!ptr::eq(&query, canonical));
+    assert_eq!(stored.as_ptr(), canonical.as_ptr());
+    assert_ne!(query.as_ptr(), canonical.as_ptr());
+
+    assert!(map.canonical_left("Goodbye").is_none());
+}
+
+#[test]
+fn remove_left_if_tests_right_value() {
+    let mut map = BiMap::new();
+    map.insert("Hello", 5);
+    map.insert("World", 7);
+
+    // the predicate passes, so the pair is removed
+    assert_eq!(Some(5), map.remove_left_if("Hello", |&right| right == 5));
+    assert_eq!(None, map.get_left("Hello"));
+    assert_eq!(None, map.get_right(&5));
+    assert_eq!(1, map.len());
+
+    // the predicate fails, so the pair is kept
+    assert_eq!(None, map.remove_left_if("World", |&right| right == 5));
+    assert_eq!(Some(&7), map.get_left("World"));
+    assert_eq!(Some(&"World"), map.get_right(&7));
+    assert_eq!(1, map.len());
+
+    // the key is absent, so the predicate is never called
+    assert_eq!(None, map.remove_left_if("Hashmaps", |_| panic!("called for an absent key")));
+    assert_eq!(1, map.len());
+}
+
+#[test]
+fn group_left_by_first_letter() {
+    let mut map = BiMap::new();
+    for (i, &word) in ["apple", "avocado", "banana", "blueberry", "cherry"].iter().enumerate() {
+        map.insert(word, i);
+    }
+
+    let groups = map.group_left_by(|left| left.as_bytes()[0]);
+    assert_eq!(3, groups.len());
+
+    let sorted = |letter: u8| {
+        let mut group = groups[&letter].clone();
+        group.sort();
+        group
+    };
+    assert_eq!(vec![(&"apple", &0), (&"avocado", &1)], sorted(b'a'));
+    assert_eq!(vec![(&"banana", &2), (&"blueberry", &3)], sorted(b'b'));
+    assert_eq!(vec![(&"cherry", &4)], sorted(b'c'));
+
+    let empty: BiMap<&str, usize> = BiMap::new();
+    assert!(empty.group_left_by(|left| left.len()).is_empty());
+}
+
+#[test]
+fn insert_validated_by_soft_cap() {
+    const CAP: usize = 3;
+    let below_cap = |map: &BiMap<u64, u64>, _: &u64, _: &u64| map.len() < CAP;
+
+    let mut map = BiMap::new();
+    for i in 0..CAP as u64 {
+        assert_eq!(Ok(()), map.insert_validated_by(i, i * 10, below_cap));
+    }
+    assert_eq!(Err((3, 30)), map.insert_validated_by(3, 30, below_cap));
+    assert_eq!(CAP, map.len());
+    assert_eq!(None, map.get_left(&3));
+    assert_eq!(None, map.get_right(&30));
+
+    // the check sees the candidate pair as well as the map
+    let mut checked = Vec::new();
+    let result = map.insert_validated_by(0, 5, |map, &left, &right| {
+        checked.push((left, right));
+        map.contains_left(&left)
+    });
+    assert_eq!(Ok(()), result);
+    assert_eq!(vec![(0, 5)], checked);
+    assert_eq!(Some(&5), map.get_left(&0));
+    assert_eq!(CAP, map.len());
+}
+
+#[test]
+fn region_hashes_isolate_changes() {
+    const REGIONS: usize = 16;
+    let original: BiMap<u64, u64> = (0..1000).map(|i| (i, i)).collect();
+    let before = original.region_hashes(REGIONS);
+    assert_eq!(REGIONS, before.len());
+
+    // the layout of the map doesn't matter, only its contents
+    let mut map: BiMap<u64, u64> = BiMapBuilder::new().capacity(4000).finish();
+    map.extend((0..1000).rev().map(|i| (i, i)));
+    assert_eq!(before, map.region_hashes(REGIONS));
+
+    map.insert(500, 5000);
+    let after = map.region_hashes(REGIONS);
+    let changed: Vec<_> = (0..REGIONS).filter(|&i| before[i] != after[i]).collect();
+    assert_eq!(1, changed.len());
+
+    map.insert(500, 500);
+    assert_eq!(before, map.region_hashes(REGIONS));
+}
+
+#[test]
+fn region_hashes_are_stable() {
+    let map: BiMap<u64, u64> = (0..20).map(|i| (i, i * i)).collect();
+    let expected = vec![
+        1_843_715_965_325_305_684,
+        11_350_298_434_538_874_060,
+        10_939_801_048_143_934_394,
+        14_429_644_926_120_412_813,
+    ];
+    assert_eq!(expected, map.region_hashes(4));
+}
+
+#[test]
+fn clear_to_baseline_shrinks_grown_map() {
+    let transitions = Arc::new(Mutex::new(Vec::new()));
+    let mut map = BiMap::with_capacity(100);
+    let log = transitions.clone();
+    map.on_resize(Box::new(move |old, new| log.lock().unwrap().push((old, new))));
+    let baseline = map.capacity();
+
+    for round in 0..3u64 {
+        map.extend((0..10_000).map(|i| (i, i + round)));
+        assert!(map.capacity() > baseline);
+
+        map.clear_to_baseline();
+        assert!(map.is_empty());
+        assert_eq!(baseline, map.capacity());
+
+        // the shrink is reported like any other resize
+        let &(old, new) = transitions.lock().unwrap().last().unwrap();
+        assert!(old > new);
+
+        map.insert(round, round);
+        assert_eq!(Some(&round), map.get_left(&round));
+        assert_eq!(Some(&round), map.get_right(&round));
+        map.clear();
+    }
+
+    // a map that hasn't grown keeps its capacity, and so does one shrunk below its baseline
+    let resizes = transitions.lock().unwrap().len();
+    map.clear_to_baseline();
+    assert_eq!(baseline, map.capacity());
+    map.shrink_to(10);
+    let shrunk = map.capacity();
+    map.clear_to_baseline();
+    assert_eq!(shrunk, map.capacity());
+    assert_eq!(resizes + 1, transitions.lock().unwrap().len());
+}
+
+#[test]
+fn keyed_right_values_use_key_fn_identity() {
+    // orders are equal for business purposes when they have the same item, but are identified by
+    // their order numbers
+    #[derive(Debug)]
+    struct Order {
+        number: u64,
+        item: &'static str,
+    }
+
+    impl PartialEq for Order {
+        fn eq(&self, other: &Self) -> bool {
+            self.item == other.item
+        }
+    }
+
+    let mut map = KeyedBiMap::with_right_key_fn(|order: &Order| order.number);
+    let first = Order { number: 1, item: "tea" };
+    let second = Order { number: 2, item: "tea" };
+    assert_eq!(first, second);
+
+    map.insert("alice", first);
+    assert_eq!((None, None), map.insert("bob", second));
+    assert_eq!(2, map.len());
+    assert_eq!(Some(&"alice"), map.get_right_key(&1));
+    assert_eq!(Some(&"bob"), map.get_right_key(&2));
+    assert_eq!(2, map.get_left("bob").unwrap().number);
+
+    // a right value with the same key replaces the old one, even though it isn't equal to it
+    let replacement = Order { number: 1, item: "coffee" };
+    let (old_right, old_left) = map.insert("carol", replacement);
+    assert!(old_right.is_none());
+    assert_eq!(Some("alice"), old_left);
+    assert_eq!(None, map.get_left("alice"));
+    assert_eq!(Some(&"carol"), map.get_right(&Order { number: 1, item: "cake" }));
+
+    assert_eq!(Some("bob"), map.remove_right_key(&2));
+    assert_eq!("coffee", map.remove_left("carol").unwrap().item);
+    assert!(map.is_empty());
+}
+
+#[test]
+fn left_cursor_removes_every_other_pair() {
+    let mut map = BiMap::new();
+    for i in 0..1000u64 {
+        map.insert(i, Tagged { id: i, tag: "new" });
+    }
+
+    let mut visited = 0;
+    let mut removed = HashSet::new();
+    {
+        let mut cursor = map.left_cursor_mut();
+        loop {
+            let left = match cursor.current() {
+                Some((&left, mut right)) => {
+                    assert_eq!(left, right.id);
+                    right.tag = "kept";
+                    left
+                }
+                None => break,
+            };
+
+            visited += 1;
+            if visited % 2 == 0 {
+                let (removed_left, right) = cursor.remove_current().unwrap();
+                assert_eq!(left, removed_left);
+                assert_eq!(left, right.id);
+                assert!(removed.insert(left));
+            } else {
+                cursor.advance();
+            }
+        }
+        assert!(cursor.remove_current().is_none());
+        cursor.advance();
+        assert!(cursor.current().is_none());
+    }
+
+    // every pair was visited exactly once
+    assert_eq!(1000, visited);
+    assert_eq!(500, removed.len());
+    assert_eq!(500, map.len());
+    for i in 0..1000 {
+        let tagged = Tagged { id: i, tag: "" };
+        if removed.contains(&i) {
+            assert!(map.get_left(&i).is_none());
+            assert!(map.get_right(&tagged).is_none());
+        } else {
+            assert_eq!("kept", map.get_left(&i).unwrap().tag);
+            assert_eq!(Some(&i), map.get_right(&tagged));
+        }
+    }
+}
+
+#[test]
+fn try_from_accepts_bijection() {
+    let map: BiMap<&str, u64> = BiMap::try_from(vec![("Hello", 5), ("World", 7)]).unwrap();
+    assert_eq!(2, map.len());
+    assert_eq!(Some(&5), map.get_left("Hello"));
+    assert_eq!(Some(&"World"), map.get_right(&7));
+
+    let empty: BiMap<&str, u64> = BiMap::try_from(Vec::new()).unwrap();
+    assert!(empty.is_empty());
+}
+
+#[test]
+fn try_from_rejects_duplicate_left() {
+    let result: Result<BiMap<&str, u64>, _> =
+        BiMap::try_from(vec![("Hello", 5), ("World", 7), ("Hello", 9)]);
+    assert_eq!(
+        Some(InjectivityError::DuplicateLeft {
+            index: 2,
+            pair: ("Hello", 9),
+        }),
+        result.err()
+    );
+}
+
+#[test]
+fn try_from_rejects_duplicate_right() {
+    let result: Result<BiMap<&str, u64>, _> =
+        BiMap::try_from(vec![("Hello", 5), ("World", 5), ("Hello", 5)]);
+    let error = result.err().unwrap();
+    assert_eq!(
+        InjectivityError::DuplicateRight {
+            index: 1,
+            pair: ("World", 5),
+        },
+        error
+    );
+    assert_eq!("the pair at index 1 repeats the right key of an earlier pair", error.to_string());
+}
+
+#[test]
+fn neighbourhood_view_shows_cluster() {
+    let mut map = identity_map();
+    // a default bimap has space for 32 pairs, in 32 * 1.1 buckets
+    let buckets = 36;
+
+    // three keys share bucket 3 as their ideal bucket, and 5 is displaced by them
+    map.insert(3, 30);
+    map.insert(3 + buckets, 31);
+    map.insert(5, 50);
+    map.insert(3 + 2 * buckets, 32);
+
+    let view = map.neighbourhood_view_left(&3);
+    assert_eq!(32, view.len());
+    assert_eq!(Some((&3, &30)), view[0]);
+    assert_eq!(Some((&(3 + buckets), &31)), view[1]);
+    assert_eq!(Some((&5, &50)), view[2]);
+    assert_eq!(Some((&(3 + 2 * buckets), &32)), view[3]);
+    assert!(view[4..].iter().all(Option::is_none));
+
+    // the view is the same for any key with the same ideal bucket, even if it isn't present
+    assert_eq!(view, map.neighbourhood_view_left(&(3 + 3 * buckets)));
+    assert_eq!(&view[2..], &map.neighbourhood_view_left(&5)[..30]);
+}
+
+/// Creates a bimap that hashes both of its sides with `ConstantHasher`.
+fn constant_map() -> BiMap<u64, u64, ConstantBuildHasher, ConstantBuildHasher> {
+    BiMapBuilder::new()
+        .left_hasher(ConstantBuildHasher::default())
+        .right_hasher(ConstantBuildHasher::default())
+        .finish()
+}
+
+#[test]
+fn constant_hasher_try_insert_gives_up() {
+    let mut map = constant_map();
+
+    // a neighbourhood holds 32 keys, and every key has the same neighbourhood whatever the size
+    for i in 0..32 {
+        assert_eq!(Ok(()), map.try_insert(i, i + 1));
+    }
+    for i in 32..1000 {
+        assert_eq!(Err((i, i + 1)), map.try_insert(i, i + 1));
+    }
+
+    assert_eq!(32, map.len());
+    assert!(map.capacity() <= 64);
+    for i in 0..32 {
+        assert_eq!(Some(&(i + 1)), map.get_left(&i));
+        assert_eq!(Some(&i), map.get_right(&(i + 1)));
+    }
+    assert_eq!(None, map.get_left(&32));
+}
+
+#[test]
+fn insert_reseeds_instead_of_panicking() {
+    let mut map: BiMap<u64, u64, Multiplicative, Multiplicative> = BiMapBuilder::new()
+        .capacity(100)
+        .left_hasher(Multiplicative { seed: 0 })
+        .right_hasher(Multiplicative { seed: 0 })
+        .reseed_on_collisions()
+        .finish();
+    let capacity = map.capacity();
+
+    // every key has the hash 0 until the hashers are replaced, so the neighbourhood of the first
+    // bucket fills up, and the next key can't be placed with these hashers however large the
+    // hashmap grows
+    for i in 0..32 {
+        map.insert(i, i + 1);
+    }
+    assert_eq!(0, map.left_hasher().seed);
+    assert_eq!((None, None), map.insert(32, 33));
+
+    assert_ne!(0, map.left_hasher().seed);
+    assert_eq!(capacity, map.capacity());
+    assert_eq!(33, map.len());
+    for i in 0..33 {
+        assert_eq!(Some(&(i + 1)), map.get_left(&i));
+        assert_eq!(Some(&i), map.get_right(&(i + 1)));
+    }
+}
+
+#[test]
+fn bad_seed_is_reseeded() {
+    let mut map: BiMap<u64, u64, Multiplicative, Multiplicative> = BiMapBuilder::new()
+        .left_hasher(Multiplicative { seed: 0 })
+        .right_hasher(Multiplicative { seed: 0 })
+        .reseed_on_collisions()
+        .finish();
+
+    for i in 0..1000 {
+        map.insert(i, i + 1);
+    }
+
+    assert_eq!(1000, map.len());
+    assert_ne!(0, map.left_hasher().seed);
+    for i in 0..1000 {
+        assert_eq!(Some(&(i + 1)), map.get_left(&i));
+        assert_eq!(Some(&i), map.get_right(&(i + 1)));
+    }
+}
+
+#[test]
+fn resolve_left_distinguishes_empty_from_missing() {
+    let mut map: BiMap<&str, &str> = BiMap::new();
+    assert_eq!(Err(ResolveError::EmptyMap), map.resolve_left("ll"));
+    assert_eq!("the bimap is empty", ResolveError::EmptyMap.to_string());
+
+    map.insert("ll", "ls -l");
+    assert_eq!(Ok(&"ls -l"), map.resolve_left("ll"));
+    assert_eq!(Err(ResolveError::NotFound), map.resolve_left("la"));
+
+    // a map that has been emptied again is reported as empty
+    map.remove_left("ll");
+    assert_eq!(Err(ResolveError::EmptyMap), map.resolve_left("ll"));
+}
@@ -0,0 +1,189 @@
+//! Randomised tests of the bimap against a simple model made from a pair of standard hashmaps.
+extern crate isomorphism;
+
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+
+use isomorphism::bitfield::BitField;
+use isomorphism::{BiMap, BiMapBuilder};
+
+/// A small deterministic random number generator (xorshift), so that failures are reproducible.
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Rng(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, limit: u64) -> u64 {
+        self.next_u64() % limit
+    }
+}
+
+/// The reference model of a bimap.
+#[derive(Default)]
+struct Model {
+    left: HashMap<u64, u64>,
+    right: HashMap<u64, u64>,
+}
+
+impl Model {
+    fn insert(&mut self, left: u64, right: u64) -> (Option<u64>, Option<u64>) {
+        let old = (self.left.get(&left).cloned(), self.right.get(&right).cloned());
+        self.remove_left(left);
+        self.remove_right(right);
+
+        self.left.insert(left, right);
+        self.right.insert(right, left);
+        old
+    }
+
+    fn remove_left(&mut self, left: u64) -> Option<u64> {
+        let right = self.left.remove(&left)?;
+        self.right.remove(&right);
+        Some(right)
+    }
+
+    fn remove_right(&mut self, right: u64) -> Option<u64> {
+        let left = self.right.remove(&right)?;
+        self.left.remove(&left);
+        Some(left)
+    }
+}
+
+/// Applies one random operation to both the bimap and the model, checking that they agree.
+fn step<LH, RH, B>(
+    rng: &mut Rng,
+    keys: u64,
+    map: &mut BiMap<u64, u64, LH, RH, B>,
+    model: &mut Model,
+) where
+    LH: BuildHasher,
+    RH: BuildHasher,
+    B: BitField,
+{
+    let left = rng.below(keys);
+    let right = rng.below(keys);
+
+    match rng.below(10) {
+        0..=4 => assert_eq!(model.insert(left, right), map.insert(left, right)),
+        5 | 6 => assert_eq!(model.remove_left(left), map.remove_left(&left)),
+        7 => assert_eq!(model.remove_right(right), map.remove_right(&right)),
+        _ => {
+            assert_eq!(model.left.get(&left), map.get_left(&left));
+            assert_eq!(model.right.get(&right), map.get_right(&right));
+        }
+    }
+
+    assert_eq!(model.left.len(), map.len());
+}
+
+/// Runs a long sequence of random operations against a bimap, validating it after every one.
+fn run<LH, RH, B>(seed: u64, keys: u64, mut map: BiMap<u64, u64, LH, RH, B>)
+where
+    LH: BuildHasher,
+    RH: BuildHasher,
+    B: BitField,
+{
+    let mut rng = Rng::new(seed);
+    let mut model = Model::default();
+
+    for _ in 0..2000 {
+        step(&mut rng, keys, &mut map, &mut model);
+        map.validate();
+    }
+
+    for (left, right) in &model.left {
+        assert_eq!(Some(right), map.get_left(left));
+        assert_eq!(Some(left), map.get_right(right));
+    }
+}
+
+#[test]
+fn model_default() {
+    for seed in 0..4 {
+        run(seed, 64, BiMap::new());
+        run(seed, 4096, BiMap::new());
+    }
+}
+
+#[test]
+fn model_small_neighbourhoods() {
+    for seed in 0..4 {
+        run(seed, 64, BiMapBuilder::new().bitfield::<u8>().finish());
+        run(seed, 4096, BiMapBuilder::new().bitfield::<u8>().finish());
+    }
+}
+
+#[test]
+fn model_small_capacity() {
+    for seed in 0..4 {
+        run(seed, 512, BiMapBuilder::new().capacity(1).bitfield::<u8>().finish());
+    }
+}
+
+/// Alternates between phases that mostly insert, growing the bimap through many resizes, and
+/// phases that mostly remove, checking the bimap against the model throughout and validating it
+/// every few hundred operations.
+fn soak<LH, RH, B>(seed: u64, mut map: BiMap<u64, u64, LH, RH, B>)
+where
+    LH: BuildHasher,
+    RH: BuildHasher,
+    B: BitField,
+{
+    let mut rng = Rng::new(seed);
+    let mut model = Model::default();
+    let keys = 1 << 15;
+
+    for op in 0..40_000 {
+        let left = rng.below(keys);
+        let right = rng.below(keys);
+
+        // insert heavy phases of 4000 operations, followed by remove heavy phases of 1000
+        let inserting = op % 5000 < 4000;
+        let insert_chance = if inserting { 9 } else { 2 };
+        match rng.below(10) {
+            chance if chance < insert_chance => {
+                assert_eq!(model.insert(left, right), map.insert(left, right))
+            }
+            chance if chance % 2 == 0 => {
+                assert_eq!(model.remove_left(left), map.remove_left(&left))
+            }
+            _ => assert_eq!(model.remove_right(right), map.remove_right(&right)),
+        }
+        assert_eq!(model.left.len(), map.len());
+
+        if op % 250 == 0 {
+            map.validate();
+        }
+    }
+
+    map.validate();
+    for (left, right) in &model.left {
+        assert_eq!(Some(right), map.get_left(left));
+        assert_eq!(Some(left), map.get_right(right));
+    }
+}
+
+#[test]
+fn soak_interleaved_resizes() {
+    for seed in 0..2 {
+        soak(seed, BiMap::new());
+        soak(seed, BiMapBuilder::new().capacity(1).bitfield::<u8>().finish());
+    }
+}
+
+#[test]
+fn model_ordered() {
+    for seed in 0..4 {
+        run(seed, 64, BiMapBuilder::new().ordered().finish());
+        run(seed, 4096, BiMapBuilder::new().capacity(1).ordered().bitfield::<u8>().finish());
+    }
+}